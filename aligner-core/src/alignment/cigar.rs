@@ -0,0 +1,128 @@
+use crate::{Error, Result};
+use std::fmt;
+use std::str::FromStr;
+
+// Operations follow SAM with the target as reference: I consumes only the
+// query, D consumes only the target
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditOp {
+    Match,
+    Equal,
+    Mismatch,
+    Insertion,
+    Deletion,
+}
+
+impl EditOp {
+    pub fn to_char(self) -> char {
+        match self {
+            EditOp::Match => 'M',
+            EditOp::Equal => '=',
+            EditOp::Mismatch => 'X',
+            EditOp::Insertion => 'I',
+            EditOp::Deletion => 'D',
+        }
+    }
+
+    pub fn from_char(c: char) -> Result<EditOp> {
+        match c {
+            'M' => Ok(EditOp::Match),
+            '=' => Ok(EditOp::Equal),
+            'X' => Ok(EditOp::Mismatch),
+            'I' => Ok(EditOp::Insertion),
+            'D' => Ok(EditOp::Deletion),
+            _ => Err(Error::CharIsNotMatchable),
+        }
+    }
+
+    pub fn consumes_query(self) -> bool {
+        self != EditOp::Deletion
+    }
+
+    pub fn consumes_target(self) -> bool {
+        self != EditOp::Insertion
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Cigar {
+    pub ops: Vec<(usize, EditOp)>,
+}
+
+impl Cigar {
+    // Run-length encodes single operations, merging neighbouring equal ones
+    pub fn from_ops(ops: &[EditOp]) -> Cigar {
+        let mut result: Vec<(usize, EditOp)> = vec![];
+
+        for op in ops.iter() {
+            match result.last_mut() {
+                Some((length, last)) if last == op => *length += 1,
+                _ => result.push((1, *op)),
+            }
+        }
+
+        Cigar { ops: result }
+    }
+
+    pub fn to_ops(&self) -> Vec<EditOp> {
+        self.ops
+            .iter()
+            .flat_map(|(length, op)| std::iter::repeat_n(*op, *length))
+            .collect()
+    }
+
+    pub fn query_length(&self) -> usize {
+        self.ops
+            .iter()
+            .filter(|(_, op)| op.consumes_query())
+            .map(|(length, _)| length)
+            .sum()
+    }
+
+    pub fn target_length(&self) -> usize {
+        self.ops
+            .iter()
+            .filter(|(_, op)| op.consumes_target())
+            .map(|(length, _)| length)
+            .sum()
+    }
+}
+
+impl fmt::Display for Cigar {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (length, op) in self.ops.iter() {
+            write!(f, "{}{}", length, op.to_char())?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for Cigar {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Cigar> {
+        let mut ops = vec![];
+        let mut length = String::new();
+
+        for c in s.chars() {
+            if c.is_ascii_digit() {
+                length.push(c);
+                continue;
+            }
+
+            let op = EditOp::from_char(c)?;
+            match length.parse::<usize>() {
+                Ok(length) if length > 0 => ops.push((length, op)),
+                _ => return Err(Error::ParseError),
+            }
+            length.clear();
+        }
+
+        if !length.is_empty() {
+            return Err(Error::ParseError);
+        }
+
+        Ok(Cigar { ops })
+    }
+}
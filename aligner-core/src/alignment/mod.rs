@@ -0,0 +1,269 @@
+use crate::alignment::cigar::{Cigar, EditOp};
+use crate::alignment::stats::AlignmentStats;
+use crate::serialization::sequence;
+use crate::{AlignmentTrait, BioData, Error, Result};
+use ndarray::Array2;
+use serde::{Deserialize, Serialize};
+
+pub mod cigar;
+pub mod report;
+pub mod stats;
+#[cfg(test)]
+mod test;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct Alignment<T: BioData> {
+    #[serde(with = "sequence")]
+    pub(crate) query: Vec<T>,
+    #[serde(with = "sequence")]
+    pub(crate) target: Vec<T>,
+    // Query and target ranges as (start, end) with a 1-based start and an
+    // exclusive end, so ACG aligned from the first residue is (1, 4)
+    pub coords: ((usize, usize), (usize, usize)),
+    pub f: f64,
+}
+
+impl<T: BioData + Into<usize> + Eq + Copy> AlignmentTrait<T> for Alignment<T> {
+    fn get_frequency_matrix(&self) -> Array2<f64> {
+        let mut frequency_matrix = Array2::<f64>::zeros((T::volume(), T::volume()));
+
+        for (x, y) in self.query.iter().zip(self.target.iter()) {
+            if *x != T::blank() && *y != T::blank() {
+                frequency_matrix[[(*y).into(), (*x).into()]] += 1f64;
+            }
+        }
+
+        frequency_matrix
+    }
+
+    fn get_alignment(&self, matrix: &Array2<f64>) -> Vec<T> {
+        let mut alignment = Vec::<T>::new();
+
+        for (x, y) in self.query.iter().zip(self.target.iter()) {
+            if *x == *y {
+                alignment.push(*x);
+            } else if (*x != T::blank())
+                && (*y != T::blank())
+                && matrix[[(*y).into(), (*x).into()]] >= 0f64
+            {
+                alignment.push(T::pos())
+            } else {
+                alignment.push(T::blank())
+            }
+        }
+
+        alignment
+    }
+
+    fn get_stats(
+        &self,
+        matrix: &Array2<f64>,
+        query_length: usize,
+        target_length: usize,
+    ) -> AlignmentStats {
+        let columns = self
+            .query
+            .iter()
+            .zip(self.target.iter())
+            .zip(self.get_edit_ops(true))
+            .filter(|((x, y), _)| **x != T::blank() || **y != T::blank())
+            .map(|((x, y), op)| match op {
+                EditOp::Equal | EditOp::Mismatch => (op, matrix[[(*y).into(), (*x).into()]]),
+                _ => (op, 0f64),
+            });
+
+        AlignmentStats::from_columns(columns, query_length, target_length)
+    }
+}
+
+impl<T: BioData + Into<usize> + Eq + Copy> Alignment<T> {
+    // Gapped query and target of equal length, e.g. restored from storage
+    pub fn new(
+        query: Vec<T>,
+        target: Vec<T>,
+        coords: ((usize, usize), (usize, usize)),
+        f: f64,
+    ) -> Result<Alignment<T>> {
+        if query.len() != target.len() {
+            return Err(Error::ValidationError);
+        }
+
+        Ok(Alignment {
+            query,
+            target,
+            coords,
+            f,
+        })
+    }
+
+    pub fn query(&self) -> &[T] {
+        &self.query
+    }
+
+    pub fn target(&self) -> &[T] {
+        &self.target
+    }
+
+    // One operation per alignment column; with extended, matches are split into
+    // = and X, otherwise every aligned pair is M. Columns gapped on both sides
+    // are skipped
+    pub fn get_edit_ops(&self, extended: bool) -> Vec<EditOp> {
+        self.query
+            .iter()
+            .zip(self.target.iter())
+            .filter_map(|(x, y)| match (*x == T::blank(), *y == T::blank()) {
+                (true, true) => None,
+                (true, false) => Some(EditOp::Deletion),
+                (false, true) => Some(EditOp::Insertion),
+                (false, false) if !extended => Some(EditOp::Match),
+                (false, false) if *x == *y => Some(EditOp::Equal),
+                (false, false) => Some(EditOp::Mismatch),
+            })
+            .collect()
+    }
+
+    pub fn get_cigar(&self, extended: bool) -> Cigar {
+        Cigar::from_ops(&self.get_edit_ops(extended))
+    }
+
+    // Rebuilds the gapped sequences from the ungapped aligned parts of query and
+    // target; = and X are checked against the residues
+    pub fn from_cigar(
+        cigar: &Cigar,
+        query: &[T],
+        target: &[T],
+        coords: ((usize, usize), (usize, usize)),
+        f: f64,
+    ) -> Result<Alignment<T>> {
+        if cigar.query_length() != query.len() || cigar.target_length() != target.len() {
+            return Err(Error::ValidationError);
+        }
+
+        let (mut query_iter, mut target_iter) = (query.iter(), target.iter());
+        let (mut query_aligned, mut target_aligned) = (vec![], vec![]);
+
+        for op in cigar.to_ops() {
+            let x = match op.consumes_query() {
+                true => *query_iter.next().unwrap(),
+                false => T::blank(),
+            };
+            let y = match op.consumes_target() {
+                true => *target_iter.next().unwrap(),
+                false => T::blank(),
+            };
+
+            if (op == EditOp::Equal && x != y) || (op == EditOp::Mismatch && x == y) {
+                return Err(Error::ValidationError);
+            }
+
+            query_aligned.push(x);
+            target_aligned.push(y);
+        }
+
+        Ok(Alignment {
+            query: query_aligned,
+            target: target_aligned,
+            coords,
+            f,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct PWMAlignment<T: BioData> {
+    pub(crate) numbered: Vec<usize>,
+    #[serde(with = "sequence")]
+    pub(crate) query: Vec<T>,
+    pub(crate) dim: usize,
+    // Same convention as Alignment::coords
+    pub coords: ((usize, usize), (usize, usize)),
+    pub f: f64,
+}
+
+impl<T: BioData + Into<usize> + Eq + Copy> AlignmentTrait<T> for PWMAlignment<T> {
+    fn get_frequency_matrix(&self) -> Array2<f64> {
+        let mut frequency_matrix = Array2::<f64>::zeros((T::volume(), self.dim));
+
+        for (x, y) in self.numbered.iter().zip(self.query.iter()) {
+            if *x != 0 && *y != T::blank() {
+                frequency_matrix[[(*y).into(), *x - 1]] += 1f64;
+            }
+        }
+
+        frequency_matrix
+    }
+
+    fn get_alignment(&self, _matrix: &Array2<f64>) -> Vec<T> {
+        let mut alignment = Vec::<T>::new();
+
+        for (x, y) in self.numbered.iter().zip(self.query.iter()) {
+            if *x != 0 {
+                alignment.push(*y)
+            } else {
+                alignment.push(T::blank())
+            }
+        }
+
+        alignment
+    }
+
+    // The PWM plays the target: a residue is identical when it is the consensus
+    // (highest scoring) residue of its column
+    fn get_stats(
+        &self,
+        matrix: &Array2<f64>,
+        query_length: usize,
+        target_length: usize,
+    ) -> AlignmentStats {
+        let columns = self
+            .numbered
+            .iter()
+            .zip(self.query.iter())
+            .filter(|(x, y)| **x != 0 || **y != T::blank())
+            .map(|(x, y)| {
+                if *x == 0 {
+                    return (EditOp::Insertion, 0f64);
+                } else if *y == T::blank() {
+                    return (EditOp::Deletion, 0f64);
+                }
+
+                let column = matrix.column(*x - 1);
+                let score = column[(*y).into()];
+                let consensus = column.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+
+                match score >= consensus {
+                    true => (EditOp::Equal, score),
+                    false => (EditOp::Mismatch, score),
+                }
+            });
+
+        AlignmentStats::from_columns(columns, query_length, target_length)
+    }
+}
+
+impl<T: BioData + Into<usize> + Eq + Copy> PWMAlignment<T> {
+    // PWM column (starting from 1) aligned to every query position, 0 for gaps
+    pub fn numbered(&self) -> &[usize] {
+        &self.numbered
+    }
+
+    pub fn query(&self) -> &[T] {
+        &self.query
+    }
+
+    pub fn dim(&self) -> usize {
+        self.dim
+    }
+
+    pub fn empty() -> Self {
+        PWMAlignment {
+            numbered: vec![],
+            query: vec![],
+            dim: 0,
+            coords: ((0, 0), (0, 0)),
+            f: 0f64,
+        }
+    }
+}
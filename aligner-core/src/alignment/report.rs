@@ -0,0 +1,134 @@
+use crate::alignment::Alignment;
+use crate::{AlignmentTrait, BioData};
+use ndarray::Array2;
+
+const WIDTH: usize = 60;
+
+#[derive(Debug, Clone)]
+pub struct ReportOptions {
+    pub width: usize,
+    pub query_name: String,
+    pub target_name: String,
+}
+
+impl Default for ReportOptions {
+    fn default() -> Self {
+        ReportOptions {
+            width: WIDTH,
+            query_name: String::from("Query"),
+            target_name: String::from("Sbjct"),
+        }
+    }
+}
+
+// BLAST-like pairwise report: a header with score and counts followed by
+// wrapped query/midline/target blocks with 1-based inclusive coordinates
+pub fn format_pairwise_report<T: BioData + Into<usize> + Eq + Copy>(
+    alignment: &Alignment<T>,
+    matrix: &Array2<f64>,
+    p_value: Option<f64>,
+    opts: &ReportOptions,
+) -> String {
+    let length = alignment.query.len();
+    let stats = alignment.get_stats(matrix, 0, 0);
+
+    let midline: Vec<char> = alignment
+        .query
+        .iter()
+        .zip(alignment.target.iter())
+        .map(|(x, y)| {
+            if *x == T::blank() || *y == T::blank() {
+                ' '
+            } else if *x == *y {
+                to_char(x)
+            } else if matrix[[(*y).into(), (*x).into()]] > 0f64 {
+                '+'
+            } else {
+                ' '
+            }
+        })
+        .collect();
+
+    let percent = |fraction: f64| (100f64 * fraction).round() as usize;
+
+    let mut report = match p_value {
+        Some(p_value) => format!("Score = {}, P-value = {:.2e}\n", alignment.f, p_value),
+        None => format!("Score = {}\n", alignment.f),
+    };
+    report.push_str(&format!(
+        "Identities = {}/{} ({}%), Positives = {}/{} ({}%), Gaps = {}/{} ({}%)\n",
+        stats.identities,
+        stats.aligned_length,
+        percent(stats.identity),
+        stats.positives,
+        stats.aligned_length,
+        percent(stats.similarity),
+        stats.gaps,
+        stats.aligned_length,
+        percent(stats.gap_fraction())
+    ));
+
+    let query: Vec<char> = alignment.query.iter().map(to_char).collect();
+    let target: Vec<char> = alignment.target.iter().map(to_char).collect();
+
+    let (mut query_start, mut target_start) = (alignment.coords.0 .0, alignment.coords.1 .0);
+    let last = query_start.max(target_start) + length;
+    let number_width = last.to_string().len();
+    let label_width = opts.query_name.len().max(opts.target_name.len());
+
+    for start in (0..length).step_by(opts.width.max(1)) {
+        let end = (start + opts.width.max(1)).min(length);
+
+        let query_block: String = query[start..end].iter().collect();
+        let target_block: String = target[start..end].iter().collect();
+        let midline_block: String = midline[start..end].iter().collect();
+
+        let query_end = query_start + count_residues(&query_block);
+        let target_end = target_start + count_residues(&target_block);
+
+        report.push('\n');
+        report.push_str(&format!(
+            "{:<lw$}  {:<nw$}  {}  {}\n",
+            opts.query_name,
+            query_start,
+            query_block,
+            query_end - 1,
+            lw = label_width,
+            nw = number_width
+        ));
+        report.push_str(&format!(
+            "{:<lw$}  {:<nw$}  {}\n",
+            "",
+            "",
+            midline_block,
+            lw = label_width,
+            nw = number_width
+        ));
+        report.push_str(&format!(
+            "{:<lw$}  {:<nw$}  {}  {}\n",
+            opts.target_name,
+            target_start,
+            target_block,
+            target_end - 1,
+            lw = label_width,
+            nw = number_width
+        ));
+
+        query_start = query_end;
+        target_start = target_end;
+    }
+
+    report
+}
+
+fn to_char<T: BioData>(elem: &T) -> char {
+    match T::convert_to_char(elem) {
+        Ok('_') => '-',
+        Ok(c) => c,
+        Err(_) => '?',
+    }
+}
+
+fn count_residues(block: &str) -> usize {
+    block.chars().filter(|c| *c != '-').count()
+}
@@ -0,0 +1,78 @@
+use crate::alignment::cigar::EditOp;
+use serde::{Deserialize, Serialize};
+
+// Counts follow BLAST: identity, similarity and gaps are fractions of all
+// alignment columns, a gap open is every run of gap columns on either side
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AlignmentStats {
+    pub aligned_length: usize,
+    pub identities: usize,
+    pub positives: usize,
+    pub gaps: usize,
+    pub gap_opens: usize,
+    pub identity: f64,
+    pub similarity: f64,
+    pub query_coverage: f64,
+    pub target_coverage: f64,
+}
+
+impl AlignmentStats {
+    // Columns are insertions, deletions or aligned pairs (= or X) with their score
+    pub(crate) fn from_columns<I>(columns: I, query_length: usize, target_length: usize) -> Self
+    where
+        I: Iterator<Item = (EditOp, f64)>,
+    {
+        let mut stats = AlignmentStats::default();
+        let (mut query_aligned, mut target_aligned) = (0usize, 0usize);
+        let mut last = None;
+
+        for (op, score) in columns {
+            stats.aligned_length += 1;
+
+            match op {
+                EditOp::Insertion | EditOp::Deletion => {
+                    stats.gaps += 1;
+                    if last != Some(op) {
+                        stats.gap_opens += 1;
+                    }
+                }
+                _ => {
+                    if op == EditOp::Equal {
+                        stats.identities += 1;
+                    }
+                    if score > 0f64 {
+                        stats.positives += 1;
+                    }
+                }
+            }
+
+            if op.consumes_query() {
+                query_aligned += 1;
+            }
+            if op.consumes_target() {
+                target_aligned += 1;
+            }
+
+            last = Some(op);
+        }
+
+        let fraction = |count: usize, total: usize| match total {
+            0 => 0f64,
+            _ => count as f64 / total as f64,
+        };
+
+        stats.identity = fraction(stats.identities, stats.aligned_length);
+        stats.similarity = fraction(stats.positives, stats.aligned_length);
+        stats.query_coverage = fraction(query_aligned, query_length);
+        stats.target_coverage = fraction(target_aligned, target_length);
+
+        stats
+    }
+
+    pub fn gap_fraction(&self) -> f64 {
+        match self.aligned_length {
+            0 => 0f64,
+            length => self.gaps as f64 / length as f64,
+        }
+    }
+}
@@ -0,0 +1,148 @@
+use crate::alignment::cigar::{Cigar, EditOp};
+use crate::alignment::report::{format_pairwise_report, ReportOptions};
+use crate::alignment::{Alignment, PWMAlignment};
+use crate::enums::{BioData, Protein, DNA};
+use crate::{get_blosum62, AlignmentTrait};
+use ndarray::Array2;
+use std::str::FromStr;
+
+fn get_alignment(query: &str, target: &str) -> Alignment<DNA> {
+    Alignment {
+        query: DNA::str_to_vec(query).unwrap(),
+        target: DNA::str_to_vec(target).unwrap(),
+        coords: ((1, 7), (1, 7)),
+        f: 0f64,
+    }
+}
+
+#[test]
+fn cigar_from_alignment_test() {
+    let alignment = get_alignment("ATG_CAAT", "ATGGC_TT");
+
+    assert_eq!(alignment.get_cigar(false).to_string(), "3M1D1M1I2M");
+    assert_eq!(alignment.get_cigar(true).to_string(), "3=1D1=1I1X1=");
+}
+
+#[test]
+fn cigar_round_trip_test() {
+    let alignment = get_alignment("ATG_CAAT", "ATGGC_TT");
+
+    for extended in [false, true] {
+        let cigar = Cigar::from_str(&alignment.get_cigar(extended).to_string()).unwrap();
+        let restored = Alignment::from_cigar(
+            &cigar,
+            &DNA::str_to_vec("ATGCAAT").unwrap(),
+            &DNA::str_to_vec("ATGGCTT").unwrap(),
+            alignment.coords,
+            alignment.f,
+        )
+        .unwrap();
+
+        assert_eq!(restored.query(), alignment.query());
+        assert_eq!(restored.target(), alignment.target());
+    }
+}
+
+#[test]
+fn cigar_validation_test() {
+    assert!(Cigar::from_str("3M2").is_err());
+    assert!(Cigar::from_str("0M").is_err());
+    assert!(Cigar::from_str("3Q").is_err());
+    assert_eq!(
+        Cigar::from_str("2=1X").unwrap().to_ops(),
+        vec![EditOp::Equal, EditOp::Equal, EditOp::Mismatch]
+    );
+
+    let query = DNA::str_to_vec("ATG").unwrap();
+    assert!(Alignment::from_cigar(
+        &Cigar::from_str("3=").unwrap(),
+        &query,
+        &query,
+        ((1, 3), (1, 3)),
+        0f64
+    )
+    .is_ok());
+    assert!(Alignment::from_cigar(
+        &Cigar::from_str("3X").unwrap(),
+        &query,
+        &query,
+        ((1, 3), (1, 3)),
+        0f64
+    )
+    .is_err());
+    assert!(Alignment::from_cigar(
+        &Cigar::from_str("4M").unwrap(),
+        &query,
+        &query,
+        ((1, 3), (1, 3)),
+        0f64
+    )
+    .is_err());
+}
+
+#[test]
+fn pairwise_report_test() {
+    let alignment = Alignment::new(
+        Protein::str_to_vec("MKTW_DRYQ").unwrap(),
+        Protein::str_to_vec("MRTWEDKYQ").unwrap(),
+        ((3, 11), (1, 10)),
+        25f64,
+    )
+    .unwrap();
+
+    let opts = ReportOptions {
+        width: 5,
+        ..ReportOptions::default()
+    };
+    let report = format_pairwise_report(&alignment, &get_blosum62(), Some(0.001), &opts);
+
+    let expected = "Score = 25, P-value = 1.00e-3
+Identities = 6/9 (67%), Positives = 8/9 (89%), Gaps = 1/9 (11%)
+
+Query  3   MKTW-  6
+           M+TW 
+Sbjct  1   MRTWE  5
+
+Query  7   DRYQ  10
+           D+YQ
+Sbjct  6   DKYQ  9
+";
+    assert_eq!(report, expected);
+}
+
+#[test]
+fn alignment_stats_test() {
+    let alignment = get_alignment("ATG__CAAT", "ATGGC_TTT");
+    let matrix = Array2::from_shape_fn((4, 4), |(y, x)| if y == x { 1f64 } else { -1f64 });
+
+    let stats = alignment.get_stats(&matrix, 10, 16);
+
+    assert_eq!(stats.aligned_length, 9);
+    assert_eq!(stats.identities, 4);
+    assert_eq!(stats.positives, 4);
+    assert_eq!(stats.gaps, 3);
+    assert_eq!(stats.gap_opens, 2);
+    assert!((stats.identity - 4f64 / 9f64).abs() < 1e-12);
+    assert!((stats.query_coverage - 0.7).abs() < 1e-12);
+    assert!((stats.target_coverage - 0.5).abs() < 1e-12);
+}
+
+#[test]
+fn pwm_alignment_stats_test() {
+    let alignment = PWMAlignment {
+        numbered: vec![1, 2, 3, 0, 4],
+        query: DNA::str_to_vec("AT_CG").unwrap(),
+        dim: 4,
+        coords: ((1, 5), (1, 5)),
+        f: 0f64,
+    };
+    let matrix = Array2::from_shape_fn((4, 4), |(y, x)| if y == x { 2f64 } else { -1f64 });
+
+    let stats = alignment.get_stats(&matrix, 4, 4);
+
+    assert_eq!(stats.aligned_length, 5);
+    assert_eq!(stats.identities, 3);
+    assert_eq!(stats.gaps, 2);
+    assert_eq!(stats.gap_opens, 2);
+    assert!((stats.target_coverage - 1f64).abs() < 1e-12);
+}
@@ -1,13 +1,33 @@
 use crate::enums::Direction;
 use crate::{AlignmentTrait, BioData};
 use ndarray::Array2;
+use serde::{Deserialize, Serialize};
 use std::marker::PhantomData;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AlignmentResult<T: BioData + Into<usize> + Copy + Eq, A: AlignmentTrait<T>> {
     pub alignment_matrix: Array2<f64>,
+    // Empty when the result was stored without it
+    #[serde(
+        default = "empty_direction_matrix",
+        skip_serializing_if = "Array2::is_empty"
+    )]
     pub direction_matrix: Array2<Direction>,
     pub alignment: A,
     pub matrix: Option<Array2<f64>>,
+    #[serde(skip)]
     pub(crate) phantom: PhantomData<T>,
 }
+
+impl<T: BioData + Into<usize> + Copy + Eq, A: AlignmentTrait<T>> AlignmentResult<T, A> {
+    // Direction matrices are as large as the DP matrix, so they are only kept
+    // when the traceback has to be reproduced
+    pub fn without_direction_matrix(mut self) -> Self {
+        self.direction_matrix = empty_direction_matrix();
+        self
+    }
+}
+
+fn empty_direction_matrix() -> Array2<Direction> {
+    Array2::from_shape_fn((0, 0), |_| Direction::Beginning)
+}
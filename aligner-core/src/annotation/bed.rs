@@ -0,0 +1,78 @@
+use crate::annotation::{
+    format_strand, malformed, parse_coord, parse_score, parse_strand, Annotation,
+};
+
+// Genome browsers expect integer scores within 0..=1000
+const MAX_SCORE: f64 = 1000f64;
+use crate::Result;
+
+pub(crate) fn read(contents: &str) -> Result<Vec<Annotation>> {
+    let mut annotations = vec![];
+
+    for (i, line) in contents.lines().enumerate() {
+        let line = line.trim_end();
+
+        if line.trim().is_empty()
+            || line.starts_with('#')
+            || line.starts_with("track")
+            || line.starts_with("browser")
+        {
+            continue;
+        }
+
+        let fields: Vec<&str> = match line.contains('\t') {
+            true => line.split('\t').collect(),
+            false => line.split_whitespace().collect(),
+        };
+
+        if fields.len() < 3 {
+            return Err(malformed(i + 1, "chrom, start and end are expected"));
+        }
+
+        let start = parse_coord(fields[1]).map_err(|_| malformed(i + 1, "invalid start"))?;
+        let end = parse_coord(fields[2]).map_err(|_| malformed(i + 1, "invalid end"))?;
+
+        if start > end {
+            return Err(malformed(i + 1, "start is greater than end"));
+        }
+
+        let field = |index: usize| fields.get(index).cloned().unwrap_or(".");
+
+        annotations.push(Annotation {
+            seqid: fields[0].to_string(),
+            source: None,
+            kind: None,
+            name: match field(3) {
+                "." => None,
+                name => Some(name.to_string()),
+            },
+            start,
+            end,
+            score: parse_score(field(4)).map_err(|_| malformed(i + 1, "invalid score"))?,
+            strand: parse_strand(field(5)).map_err(|_| malformed(i + 1, "invalid strand"))?,
+            attributes: vec![],
+        });
+    }
+
+    Ok(annotations)
+}
+
+pub(crate) fn write(annotations: &[Annotation]) -> String {
+    let mut result = String::new();
+
+    for annotation in annotations.iter() {
+        result.push_str(&format!(
+            "{}\t{}\t{}\t{}\t{}\t{}\n",
+            annotation.seqid,
+            annotation.start,
+            annotation.end,
+            annotation.name.as_deref().unwrap_or("."),
+            annotation
+                .score
+                .map_or(0f64, |score| score.round().clamp(0f64, MAX_SCORE)),
+            format_strand(annotation.strand)
+        ));
+    }
+
+    result
+}
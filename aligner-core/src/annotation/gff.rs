@@ -0,0 +1,128 @@
+use crate::annotation::{
+    format_strand, malformed, parse_coord, parse_score, parse_strand, Annotation,
+};
+use crate::Result;
+
+const GFF3_HEADER: &str = "##gff-version 3";
+// Sequence Ontology term for features of unknown type
+const DEFAULT_KIND: &str = "region";
+// Characters escaped in GFF3 columns, the percent sign goes first when escaping
+const ESCAPED: [(&str, &str); 6] = [
+    ("%25", "%"),
+    ("%09", "\t"),
+    ("%3B", ";"),
+    ("%3D", "="),
+    ("%26", "&"),
+    ("%2C", ","),
+];
+
+pub(crate) fn read(contents: &str) -> Result<Vec<Annotation>> {
+    let mut annotations = vec![];
+
+    for (i, line) in contents.lines().enumerate() {
+        // Embedded sequences end the annotation part
+        if line.starts_with("##FASTA") {
+            break;
+        }
+
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let fields: Vec<&str> = line.split('\t').collect();
+
+        if fields.len() != 9 {
+            return Err(malformed(i + 1, "nine tab separated columns are expected"));
+        }
+
+        let start = parse_coord(fields[3]).map_err(|_| malformed(i + 1, "invalid start"))?;
+        let end = parse_coord(fields[4]).map_err(|_| malformed(i + 1, "invalid end"))?;
+
+        if start == 0 || start > end {
+            return Err(malformed(i + 1, "start is out of 1..=end"));
+        }
+
+        let attributes: Vec<(String, String)> = fields[8]
+            .split(';')
+            .filter(|attribute| !attribute.trim().is_empty() && *attribute != ".")
+            .map(|attribute| {
+                let (key, value) = attribute.split_once('=').unwrap_or((attribute, ""));
+                (unescape(key.trim()), unescape(value))
+            })
+            .collect();
+
+        let attribute = |key: &str| {
+            attributes
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value.clone())
+        };
+
+        annotations.push(Annotation {
+            seqid: unescape(fields[0]),
+            source: match fields[1] {
+                "." => None,
+                source => Some(unescape(source)),
+            },
+            kind: Some(unescape(fields[2])),
+            name: attribute("Name").or_else(|| attribute("ID")),
+            start: start - 1,
+            end,
+            score: parse_score(fields[5]).map_err(|_| malformed(i + 1, "invalid score"))?,
+            strand: parse_strand(fields[6]).map_err(|_| malformed(i + 1, "invalid strand"))?,
+            attributes,
+        });
+    }
+
+    Ok(annotations)
+}
+
+pub(crate) fn write(annotations: &[Annotation]) -> String {
+    let mut result = format!("{}\n", GFF3_HEADER);
+
+    for annotation in annotations.iter() {
+        let attributes = annotation
+            .attributes
+            .iter()
+            .map(|(key, value)| format!("{}={}", escape(key), escape(value)))
+            .collect::<Vec<String>>()
+            .join(";");
+
+        result.push_str(&format!(
+            "{}\t{}\t{}\t{}\t{}\t{}\t{}\t.\t{}\n",
+            escape(&annotation.seqid),
+            annotation.source.as_deref().map_or(".".to_string(), escape),
+            escape(annotation.kind.as_deref().unwrap_or(DEFAULT_KIND)),
+            annotation.start + 1,
+            annotation.end,
+            annotation
+                .score
+                .map_or(".".to_string(), |score| score.to_string()),
+            format_strand(annotation.strand),
+            match attributes.is_empty() {
+                true => ".".to_string(),
+                false => attributes,
+            }
+        ));
+    }
+
+    result
+}
+
+fn escape(value: &str) -> String {
+    ESCAPED
+        .iter()
+        .fold(value.to_string(), |text, (code, symbol)| {
+            text.replace(symbol, code)
+        })
+}
+
+fn unescape(value: &str) -> String {
+    ESCAPED
+        .iter()
+        .rev()
+        .fold(value.to_string(), |text, (code, symbol)| {
+            text.replace(code, symbol)
+                .replace(&code.to_lowercase(), symbol)
+        })
+}
@@ -0,0 +1,132 @@
+use crate::enums::Strand;
+use crate::{Error, Result};
+use std::path::Path;
+use std::str::FromStr;
+
+mod bed;
+mod gff;
+#[cfg(test)]
+mod test;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AnnotationFormat {
+    Bed,
+    Gff3,
+}
+
+impl FromStr for AnnotationFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<AnnotationFormat> {
+        match s.to_lowercase().as_str() {
+            "bed" => Ok(AnnotationFormat::Bed),
+            "gff" | "gff3" => Ok(AnnotationFormat::Gff3),
+            _ => Err(Error::ValidationError),
+        }
+    }
+}
+
+impl AnnotationFormat {
+    // Format by the file extension, e.g. repeats.bed or repeats.gff3
+    pub fn from_path(path: &Path) -> Option<AnnotationFormat> {
+        path.extension()
+            .and_then(|extension| extension.to_str())
+            .and_then(|extension| AnnotationFormat::from_str(extension).ok())
+    }
+}
+
+// A feature of BED or GFF3 with 0-based half-open coordinates
+#[derive(Debug, Clone, PartialEq)]
+pub struct Annotation {
+    pub seqid: String,
+    pub source: Option<String>,
+    // GFF3 type column
+    pub kind: Option<String>,
+    // BED name column or the Name (ID) attribute of GFF3
+    pub name: Option<String>,
+    pub start: usize,
+    pub end: usize,
+    pub score: Option<f64>,
+    pub strand: Option<Strand>,
+    pub attributes: Vec<(String, String)>,
+}
+
+impl Annotation {
+    // Type filter, either the GFF3 type or the feature name should be listed
+    pub fn is_one_of(&self, kinds: &[String]) -> bool {
+        kinds
+            .iter()
+            .any(|kind| self.kind.as_ref() == Some(kind) || self.name.as_ref() == Some(kind))
+    }
+}
+
+pub fn read_annotations(contents: &str, format: AnnotationFormat) -> Result<Vec<Annotation>> {
+    match format {
+        AnnotationFormat::Bed => bed::read(contents),
+        AnnotationFormat::Gff3 => gff::read(contents),
+    }
+}
+
+pub fn write_annotations(annotations: &[Annotation], format: AnnotationFormat) -> String {
+    match format {
+        AnnotationFormat::Bed => bed::write(annotations),
+        AnnotationFormat::Gff3 => gff::write(annotations),
+    }
+}
+
+pub fn load_annotations(path: &Path, format: AnnotationFormat) -> Result<Vec<Annotation>> {
+    match std::fs::read_to_string(path) {
+        Ok(contents) => read_annotations(&contents, format),
+        Err(_) => Err(Error::IOError),
+    }
+}
+
+pub fn save_annotations(
+    path: &Path,
+    annotations: &[Annotation],
+    format: AnnotationFormat,
+) -> Result<()> {
+    match std::fs::write(path, write_annotations(annotations, format)) {
+        Ok(_) => Ok(()),
+        Err(_) => Err(Error::IOError),
+    }
+}
+
+fn format_strand(strand: Option<Strand>) -> &'static str {
+    match strand {
+        Some(Strand::Forward) => "+",
+        Some(Strand::Reverse) => "-",
+        None => ".",
+    }
+}
+
+fn parse_strand(value: &str) -> Result<Option<Strand>> {
+    match value {
+        "+" => Ok(Some(Strand::Forward)),
+        "-" => Ok(Some(Strand::Reverse)),
+        "." | "?" => Ok(None),
+        _ => Err(Error::ParseError),
+    }
+}
+
+fn parse_score(value: &str) -> Result<Option<f64>> {
+    match value {
+        "." => Ok(None),
+        value => match value.parse() {
+            Ok(score) => Ok(Some(score)),
+            Err(_) => Err(Error::ParseError),
+        },
+    }
+}
+
+fn parse_coord(value: &str) -> Result<usize> {
+    match value.parse() {
+        Ok(coord) => Ok(coord),
+        Err(_) => Err(Error::ParseError),
+    }
+}
+
+fn malformed(line: usize, message: &str) -> Error {
+    error!("Malformed annotation at line {}: {}", line, message);
+    Error::ParseError
+}
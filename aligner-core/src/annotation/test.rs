@@ -0,0 +1,83 @@
+use crate::annotation::{read_annotations, write_annotations, AnnotationFormat};
+use crate::enums::Strand;
+use crate::Error;
+use std::path::Path;
+use std::str::FromStr;
+
+const BED: &str = "\
+track name=repeats
+chr1\t10\t20\tAluY\t300\t+
+chr1\t40\t45\tL1\t.\t-
+chr2 5 6
+";
+
+const GFF3: &str = "\
+##gff-version 3
+chr1\tRepeatMasker\tdispersed_repeat\t11\t20\t2.5e+03\t-\t.\tID=r1;Name=Alu%3BY
+chr1\tDfam\tsimilarity\t41\t45\t.\t?\t.\tTarget=L1 1 5
+##FASTA
+>chr1
+ACGT
+";
+
+#[test]
+fn bed_read_test() {
+    let annotations = read_annotations(BED, AnnotationFormat::Bed).unwrap();
+
+    assert_eq!(annotations.len(), 3);
+    assert_eq!((annotations[0].start, annotations[0].end), (10, 20));
+    assert_eq!(annotations[0].name.as_deref(), Some("AluY"));
+    assert_eq!(annotations[0].score, Some(300f64));
+    assert_eq!(annotations[1].strand, Some(Strand::Reverse));
+    assert_eq!(annotations[2].seqid, "chr2");
+    assert_eq!(annotations[2].strand, None);
+    assert!(annotations[0].is_one_of(&["AluY".to_string()]));
+
+    assert!(matches!(
+        read_annotations("chr1\t20\t10\n", AnnotationFormat::Bed),
+        Err(Error::ParseError)
+    ));
+}
+
+#[test]
+fn gff3_read_test() {
+    let annotations = read_annotations(GFF3, AnnotationFormat::Gff3).unwrap();
+
+    assert_eq!(annotations.len(), 2);
+    assert_eq!((annotations[0].start, annotations[0].end), (10, 20));
+    assert_eq!(annotations[0].kind.as_deref(), Some("dispersed_repeat"));
+    assert_eq!(annotations[0].name.as_deref(), Some("Alu;Y"));
+    assert_eq!(annotations[0].strand, Some(Strand::Reverse));
+    assert_eq!(annotations[1].source.as_deref(), Some("Dfam"));
+    assert_eq!(annotations[1].strand, None);
+    assert!(annotations[1].is_one_of(&["similarity".to_string()]));
+
+    assert!(matches!(
+        read_annotations(
+            "chr1\t.\trepeat\t0\t10\t.\t+\t.\t.\n",
+            AnnotationFormat::Gff3
+        ),
+        Err(Error::ParseError)
+    ));
+    assert_eq!(
+        AnnotationFormat::from_path(Path::new("rm.GFF3")),
+        Some(AnnotationFormat::Gff3)
+    );
+    assert!(AnnotationFormat::from_str("csv").is_err());
+}
+
+#[test]
+fn write_round_trip_test() {
+    let annotations = read_annotations(GFF3, AnnotationFormat::Gff3).unwrap();
+
+    let gff3 = write_annotations(&annotations, AnnotationFormat::Gff3);
+    assert!(gff3.starts_with("##gff-version 3\n"));
+    assert!(gff3.contains("\t11\t20\t2500\t-\t.\tID=r1;Name=Alu%3BY\n"));
+    assert_eq!(
+        read_annotations(&gff3, AnnotationFormat::Gff3).unwrap(),
+        annotations
+    );
+
+    let bed = write_annotations(&annotations, AnnotationFormat::Bed);
+    assert_eq!(bed, "chr1\t10\t20\tAlu;Y\t1000\t-\nchr1\t40\t45\t.\t0\t.\n");
+}
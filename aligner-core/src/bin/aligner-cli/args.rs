@@ -12,9 +12,50 @@ pub(crate) struct Args {
     #[clap(short, long, default_value_t = 2f64)]
     pub(crate) extension: f64,
 
+    // Shorthand for --mode global
     #[clap(short, long)]
     pub(crate) global: bool,
 
-    #[clap(short, long, default_value_t = String::from("out/result.txt"))]
-    pub(crate) output: String,
+    // local, global, semi-global (the whole query in a part of the target) or heuristic
+    #[clap(long, conflicts_with = "global")]
+    pub(crate) mode: Option<String>,
+
+    // protein, dna or auto (DNA when every record consists of ACGTN)
+    #[clap(long, default_value_t = String::from("auto"))]
+    pub(crate) alphabet: String,
+
+    // blosum62, blastn or a path to a matrix file; blosum62 for proteins and
+    // blastn for DNA by default
+    #[clap(long)]
+    pub(crate) matrix: Option<String>,
+
+    // Heuristic mode parameters, zero r-squared stands for the matrix size
+    #[clap(long, default_value_t = 0f64)]
+    pub(crate) kd: f64,
+
+    #[clap(long, default_value_t = 0f64)]
+    pub(crate) rsquared: f64,
+
+    // first aligns the first record against every other one, all aligns every pair
+    #[clap(long, default_value_t = String::from("first"))]
+    pub(crate) pairs: String,
+
+    // File for the reports or SAM records, stdout by default
+    #[clap(short, long)]
+    pub(crate) output: Option<String>,
+
+    #[clap(long)]
+    pub(crate) mask: bool,
+
+    #[clap(long)]
+    pub(crate) composition_adjustment: bool,
+
+    #[clap(long)]
+    pub(crate) sam: bool,
+
+    #[clap(long)]
+    pub(crate) dot_plot: Option<String>,
+
+    #[clap(long)]
+    pub(crate) heatmap: Option<String>,
 }
@@ -1,14 +1,30 @@
-use aligner_core::enums::Protein;
+use aligner_core::alignment::report::{format_pairwise_report, ReportOptions};
+use aligner_core::alignment::Alignment;
+use aligner_core::alignment_result::AlignmentResult;
+use aligner_core::enums::{BioData, Protein, DNA};
+use aligner_core::heuristic::HeuristicAligner;
+use aligner_core::masking::{apply_mask, seg, SegOptions};
+use aligner_core::matrices::{get_matrix_by_name, load_matrix};
+use aligner_core::reader::{SequenceReader, SequenceRecord};
+use aligner_core::sam::{get_mapq, write_sam, SamRecord, MAPQ_UNAVAILABLE};
 use aligner_core::simple::*;
-use aligner_core::{get_blosum62, AlignerTrait, AlignmentTrait};
+use aligner_core::statistics::composition::{get_composition, rescale_matrix};
+use aligner_core::statistics::karlin::calculate_ungapped_params;
+use aligner_core::visualisation::{DotPlotMode, Plot};
+use aligner_core::{get_robinson_frequencies, AlignerTrait, AlignmentTrait, Heuristics};
 use aligner_helpers::files::*;
 use clap::Parser;
-use seq_io::fasta::Reader;
+use ndarray::{Array1, Array2};
 use std::env;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 pub mod args;
 
+const DOT_PLOT_WINDOW: usize = 11;
+const DOT_PLOT_THRESHOLD: f64 = 11f64;
+const PLOT_CELL: usize = 4;
+const NUCLEOTIDES: &[u8] = b"ACGTN";
+
 fn main() {
     let args = args::Args::parse();
 
@@ -18,37 +34,315 @@ fn main() {
         args.input
     );
 
-    let contents = load_file_contents(Path::new(&path));
-    let bytes = contents.as_bytes();
+    let seqs: Vec<SequenceRecord> =
+        match SequenceReader::from_path(Path::new(&path)).and_then(|reader| reader.collect()) {
+            Ok(seqs) => seqs,
+            Err(err) => panic!("Error with collecting files: {:?}", err),
+        };
+
+    if seqs.len() < 2 {
+        panic!("There should be at least 2 sequences, not {}", seqs.len())
+    }
+
+    let pairs: Vec<(usize, usize)> = match args.pairs.as_str() {
+        "first" => (1..seqs.len()).map(|j| (0, j)).collect(),
+        "all" => (0..seqs.len())
+            .flat_map(|i| (i + 1..seqs.len()).map(move |j| (i, j)))
+            .collect(),
+        pairs => panic!("Unknown pairs {}, use first or all", pairs),
+    };
+
+    let is_dna = match args.alphabet.as_str() {
+        "protein" => false,
+        "dna" => true,
+        "auto" => seqs.iter().all(|seq| {
+            seq.sequence
+                .iter()
+                .all(|elem| NUCLEOTIDES.contains(&elem.to_ascii_uppercase()))
+        }),
+        alphabet => panic!("Unknown alphabet {}, use protein, dna or auto", alphabet),
+    };
+
+    // SEG replaces low-complexity residues by X, DNA has no such symbol
+    if args.mask && is_dna {
+        panic!("Masking is only available for proteins")
+    }
+
+    if args.sam && !is_dna {
+        panic!("SAM output is only available for DNA")
+    }
+
+    if args.sam {
+        write_sam_output(&args, &seqs, &pairs);
+    } else if is_dna {
+        let background = Array1::<f64>::from_elem(DNA::volume(), 1f64 / DNA::volume() as f64);
+        write_reports::<DNA>(&args, &seqs, &pairs, "blastn", &background);
+    } else {
+        write_reports::<Protein>(
+            &args,
+            &seqs,
+            &pairs,
+            "blosum62",
+            &get_robinson_frequencies(),
+        );
+    }
+}
+
+fn get_mode(args: &args::Args) -> &str {
+    match (&args.mode, args.global) {
+        (Some(mode), _) => mode.as_str(),
+        (None, true) => "global",
+        (None, false) => "local",
+    }
+}
 
-    let mut reader = Reader::new(bytes);
+// A built-in matrix by name or a matrix file for the chosen alphabet
+fn get_matrix<T: BioData + Into<usize>>(args: &args::Args, default: &str) -> Array2<f64> {
+    let name = args.matrix.as_deref().unwrap_or(default);
 
-    let seqs: Vec<_> = match reader.records().collect() {
-        Ok(seqs) => seqs,
-        Err(err) => panic!("Error with collecting files: {}", err),
+    let matrix = match Path::new(name).is_file() {
+        true => load_matrix::<T>(Path::new(name)),
+        false => get_matrix_by_name(name),
     };
 
-    if seqs.len() != 2 {
-        panic!("There's should be 2 sequences, not {}", seqs.len())
+    match matrix {
+        Ok(matrix) if matrix.dim() == (T::volume(), T::volume()) => matrix,
+        Ok(_) => panic!("Matrix {} doesn't fit the alphabet", name),
+        Err(err) => panic!("Unable to get matrix {}: {:?}", name, err),
     }
+}
 
-    let blosum62 = &get_blosum62();
-    let query = std::str::from_utf8(&seqs[0].seq).unwrap().to_string();
-    let target = std::str::from_utf8(&seqs[1].seq).unwrap().to_string();
+// Residues the aligners work with, lowercase input is treated as uppercase
+fn prepare<T: BioData + Into<usize> + Copy>(args: &args::Args, raw: &[u8]) -> Vec<T> {
+    let raw = raw.to_ascii_uppercase();
+    let raw = match args.mask {
+        true => {
+            let sequence = T::from_u8_vec_with_freqs_and_indices(&raw).unwrap().0;
+            let mask = seg(&sequence, &SegOptions::default());
+            apply_mask::<T>(&raw, &mask, b'X')
+        }
+        false => raw,
+    };
 
-    let result;
+    T::from_u8_vec_with_freqs_and_indices(&raw).unwrap().0
+}
 
-    if args.global {
-        result = SimpleGlobalAligner::<Protein>::from_str_seqs(&query, &target)
+fn align<T: BioData + Into<usize> + Copy + Eq>(
+    args: &args::Args,
+    query: &[T],
+    target: &[T],
+    matrix: &Array2<f64>,
+) -> AlignmentResult<T, Alignment<T>> {
+    let result = match get_mode(args) {
+        "local" => SimpleLocalAligner::<T>::from_seqs(query, target)
             .unwrap()
-            .perform_alignment(args.deletions, args.extension, blosum62, None)
-            .unwrap();
-    } else {
-        result = SimpleLocalAligner::<Protein>::from_str_seqs(&query, &target)
+            .perform_alignment(args.deletions, args.extension, matrix, None),
+        "global" => SimpleGlobalAligner::<T>::from_seqs(query, target)
+            .unwrap()
+            .perform_alignment(args.deletions, args.extension, matrix, None),
+        "semi-global" => SemiGlobalAligner::<T>::from_seqs(query, target)
             .unwrap()
-            .perform_alignment(args.deletions, args.extension, blosum62, None)
+            .perform_alignment(args.deletions, args.extension, matrix, None),
+        "heuristic" => HeuristicAligner::<T>::from_seqs(query, target)
+            .unwrap()
+            .perform_alignment(
+                args.deletions,
+                args.extension,
+                matrix,
+                Some(Heuristics {
+                    kd: args.kd,
+                    r_squared: args.rsquared,
+                    frequencies: get_composition(&[query, target].concat()),
+                }),
+            ),
+        mode => panic!(
+            "Unknown mode {}, use local, global, semi-global or heuristic",
+            mode
+        ),
+    };
+
+    result.unwrap()
+}
+
+fn write_reports<T: BioData + Into<usize> + Copy + Eq>(
+    args: &args::Args,
+    records: &[SequenceRecord],
+    pairs: &[(usize, usize)],
+    default_matrix: &str,
+    background: &Array1<f64>,
+) {
+    let base_matrix = get_matrix::<T>(args, default_matrix);
+    let seqs: Vec<Vec<T>> = records
+        .iter()
+        .map(|record| prepare(args, &record.sequence))
+        .collect();
+
+    let mut output = String::new();
+
+    for (n, (i, j)) in pairs.iter().enumerate() {
+        let (query, target) = (&seqs[*i], &seqs[*j]);
+        let (query_id, target_id) = (&records[*i].id, &records[*j].id);
+
+        if pairs.len() > 1 {
+            if n > 0 {
+                output.push('\n');
+            }
+            output.push_str(&format!("# {} vs {}\n\n", query_id, target_id));
+        }
+
+        let (query_freqs, target_freqs) = (get_composition(query), get_composition(target));
+
+        let matrix = if args.composition_adjustment {
+            match rescale_matrix(&base_matrix, background, &query_freqs, &target_freqs) {
+                Ok(matrix) => matrix,
+                Err(err) => {
+                    eprintln!(
+                        "Composition adjustment failed, using the original matrix: {:?}",
+                        err
+                    );
+                    base_matrix.clone()
+                }
+            }
+        } else {
+            base_matrix.clone()
+        };
+
+        let result = align(args, query, target, &matrix);
+        // Heuristic scores come from the refined matrix
+        let matrix = result.matrix.as_ref().unwrap_or(&matrix);
+
+        let p_value = calculate_ungapped_params(matrix, &query_freqs, &target_freqs)
+            .ok()
+            .map(|params| params.get_p_value(query.len(), target.len(), result.alignment.f));
+
+        output.push_str(&format_pairwise_report(
+            &result.alignment,
+            matrix,
+            p_value,
+            &ReportOptions::default(),
+        ));
+
+        let stats = result
+            .alignment
+            .get_stats(matrix, query.len(), target.len());
+
+        output.push_str(&format!(
+            "\nAligned length = {}, Gap opens = {}, Query coverage = {:.1}%, Target coverage = {:.1}%\n",
+            stats.aligned_length,
+            stats.gap_opens,
+            100f64 * stats.query_coverage,
+            100f64 * stats.target_coverage
+        ));
+
+        if let Some(path) = &args.dot_plot {
+            let plot = Plot::dot_plot(
+                query,
+                target,
+                matrix,
+                DotPlotMode::Window {
+                    size: DOT_PLOT_WINDOW,
+                    threshold: DOT_PLOT_THRESHOLD,
+                },
+            )
             .unwrap();
+            write_plot(&get_plot_path(path, pairs, query_id, target_id), &plot);
+        }
+
+        if let Some(path) = &args.heatmap {
+            let (rows, columns) = result.alignment_matrix.dim();
+            let end = match get_mode(args) {
+                "global" => Some((rows - 1, columns - 1)),
+                "semi-global" => Some((result.alignment.coords.1 .1 - 1, columns - 1)),
+                _ => None,
+            };
+            let plot =
+                Plot::heatmap(&result.alignment_matrix, &result.direction_matrix, end).unwrap();
+            write_plot(&get_plot_path(path, pairs, query_id, target_id), &plot);
+        }
+    }
+
+    write_output(args, &output);
+}
+
+// Reports and SAM go to --output when it is given and to stdout otherwise
+fn write_output(args: &args::Args, contents: &str) {
+    match &args.output {
+        Some(path) => write_to_file(Path::new(path), contents),
+        None => print!("{}", contents),
+    }
+}
+
+// Plots of several pairs get the record ids before the extension
+fn get_plot_path(path: &str, pairs: &[(usize, usize)], query_id: &str, target_id: &str) -> PathBuf {
+    let path = PathBuf::from(path);
+
+    if pairs.len() < 2 {
+        return path;
+    }
+
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let name = match path.extension() {
+        Some(extension) => format!(
+            "{}.{}-{}.{}",
+            stem,
+            query_id,
+            target_id,
+            extension.to_string_lossy()
+        ),
+        None => format!("{}.{}-{}", stem, query_id, target_id),
+    };
+
+    path.with_file_name(name)
+}
+
+// PNG for .png paths, SVG otherwise
+fn write_plot(path: &Path, plot: &Plot) {
+    let contents = match path.extension().and_then(|extension| extension.to_str()) {
+        Some("png") => plot.to_png(PLOT_CELL).unwrap(),
+        _ => plot.to_svg(PLOT_CELL as f64).into_bytes(),
+    };
+
+    std::fs::write(path, contents).unwrap();
+}
+
+// Query records are aligned as reads against target records as references
+fn write_sam_output(args: &args::Args, seqs: &[SequenceRecord], pairs: &[(usize, usize)]) {
+    let base_matrix = get_matrix::<DNA>(args, "blastn");
+    let parsed: Vec<(Vec<DNA>, Array1<f64>)> = seqs
+        .iter()
+        .map(|seq| {
+            let (sequence, freqs, _) =
+                DNA::from_u8_vec_with_freqs_and_indices(&seq.sequence.to_ascii_uppercase())
+                    .unwrap();
+            (sequence, freqs)
+        })
+        .collect();
+
+    let mut references: Vec<(String, usize)> = vec![];
+    let mut records = vec![];
+
+    for (i, j) in pairs.iter() {
+        let ((query, query_freqs), (target, target_freqs)) = (&parsed[*i], &parsed[*j]);
+        let result = align(args, query, target, &base_matrix);
+        let matrix = result.matrix.as_ref().unwrap_or(&base_matrix);
+
+        let mapq = match calculate_ungapped_params(matrix, query_freqs, target_freqs) {
+            Ok(params) => {
+                get_mapq(params.get_p_value(query.len(), target.len(), result.alignment.f))
+            }
+            Err(_) => MAPQ_UNAVAILABLE,
+        };
+
+        let (query_name, reference_name) = (seqs[*i].id.as_str(), seqs[*j].id.as_str());
+        records.push(
+            SamRecord::from_alignment(query_name, query, reference_name, &result.alignment, mapq)
+                .unwrap(),
+        );
+
+        if !references.iter().any(|(name, _)| name == reference_name) {
+            references.push((reference_name.to_string(), target.len()));
+        }
     }
 
-    println!("{:?}", result.alignment.get_alignment(blosum62))
+    write_output(args, &write_sam(&references, &records, "aligner-cli"));
 }
@@ -9,6 +9,7 @@ pub(crate) struct Args {
     #[clap(short, long)]
     pub(crate) output: Option<String>,
 
+    // Known repeats to mask: a result CSV of a previous run, .bed or .gff3
     #[clap(long)]
     pub(crate) csv: Option<String>,
 
@@ -41,4 +42,67 @@ pub(crate) struct Args {
 
     #[clap(long)]
     pub(crate) reverse: bool,
+
+    // Score windows with a profile HMM built from the previous cycle's hits;
+    // results still hold the PWM summarising those hits, not the HMM
+    #[clap(long)]
+    pub(crate) hmm: bool,
+
+    // Motif file to start the search from instead of a random PWM; its width
+    // is the length of the searched repeats
+    #[clap(long)]
+    pub(crate) seed_motif: Option<String>,
+
+    #[clap(long, default_value_t = String::from("meme"))]
+    pub(crate) seed_motif_format: String,
+
+    // Motif of the file to start from, the first one by default
+    #[clap(long)]
+    pub(crate) seed_motif_name: Option<String>,
+
+    #[clap(long, default_value_t = String::from("json"))]
+    pub(crate) matrices_format: String,
+
+    // Result format: csv, bed or gff3
+    #[clap(long, default_value_t = String::from("csv"))]
+    pub(crate) output_format: String,
+
+    // Input with found copies masked by N, or by lowercase with --soft-mask
+    #[clap(long)]
+    pub(crate) masked_fasta: Option<String>,
+
+    #[clap(long)]
+    pub(crate) soft_mask: bool,
+
+    // Multi-FASTA of all found copies
+    #[clap(long)]
+    pub(crate) copies_fasta: Option<String>,
+
+    #[clap(long)]
+    pub(crate) logo: bool,
+
+    #[clap(long)]
+    pub(crate) random_seed: Option<u64>,
+
+    #[clap(long, default_value_t = String::from("shuffle"))]
+    pub(crate) null_model: String,
+
+    #[clap(long, default_value_t = 0.05)]
+    pub(crate) significance: f64,
+
+    #[clap(long)]
+    pub(crate) dust: bool,
+
+    // Comma separated GenBank/EMBL feature keys to mask, e.g. repeat_region,CDS;
+    // also keeps only the listed GFF3 types or BED names of a --csv annotation
+    #[clap(long)]
+    pub(crate) mask_features: Option<String>,
+
+    // Keeps only --csv annotations of the strand, + or -
+    #[clap(long)]
+    pub(crate) mask_strand: Option<String>,
+
+    // Sequence name with an optional 0-based range, e.g. chr1:1000-2000 (.2bit input only)
+    #[clap(long)]
+    pub(crate) region: Option<String>,
 }
@@ -1,38 +1,63 @@
-use crate::cmd::{CMDOptions, CMDResult, DIRECT, INVERSE};
+use crate::cmd::{CMDOptions, CMDOutput, CMDResult, DIRECT, INVERSE};
 use crate::engine::calc::perform_calculation_per_sequence;
-use crate::engine::sequences::{import_fasta_file, prepare_sequence};
-use crate::error::Error;
-use aligner_helpers::csv::read_csv;
+use crate::engine::sequences::{import_masks, import_sequences, prepare_sequence};
+use crate::engine::task::REVERSED_SUFFIX;
+use aligner_core::get_rng;
 use std::collections::HashMap;
 
 pub(crate) fn run_csv_cmd(opts: &CMDOptions) -> CMDResult {
     info!("Entering exploring mode with csv support!!");
 
-    let seqs = match import_fasta_file(opts.fasta_path.as_ref().unwrap().as_path()) {
+    let seqs = match import_sequences(
+        opts.fasta_path.as_ref().unwrap().as_path(),
+        opts.region.as_ref(),
+    ) {
         Ok(seqs) => seqs,
         Err(err) => return Err(err),
     };
 
-    let sequence_data = match read_csv(opts.csv_path.as_ref().unwrap().as_path()) {
-        Ok(data) => data,
-        Err(err) => {
-            return Err(Error {
-                msg: err.to_string(),
-            })
-        }
-    };
+    let masks = import_masks(
+        opts.csv_path.as_ref().unwrap().as_path(),
+        &opts.mask_features,
+        opts.mask_strand,
+    )?;
 
     let mut result = HashMap::new();
+    let mut lengths = HashMap::new();
+    let mut rng = get_rng(opts.random_seed);
 
-    for raw_seq in seqs.iter() {
-        let head = std::str::from_utf8(&raw_seq.head).unwrap();
+    for raw_seq in seqs {
+        let (raw_seq, start) = raw_seq?;
+        let head = raw_seq.head();
+        let head = head.as_str();
 
-        let prepared_seq = match sequence_data.get(head) {
-            Some(data) => prepare_sequence(&raw_seq.seq, data),
-            None => raw_seq.seq.clone(),
-        };
+        // Previous runs are keyed by the whole header, annotations by the id
+        let ranges = masks
+            .get(head)
+            .or_else(|| masks.get(&raw_seq.id))
+            .map(Vec::as_slice)
+            .unwrap_or(&[]);
+        let end = start + raw_seq.sequence.len();
+
+        // Masks are in genome coordinates, so a region keeps only its part of them
+        let ranges = ranges.iter().filter_map(|(left, right)| match opts.region {
+            Some(_) if *right <= start || *left >= end => None,
+            Some(_) => Some(((*left).max(start) - start, (*right).min(end) - start)),
+            None => Some((*left, *right)),
+        });
+
+        let prepared_seq = prepare_sequence(
+            &raw_seq.sequence,
+            head,
+            ranges.chain(raw_seq.feature_ranges(&opts.mask_features)),
+        )?;
+
+        let mut sequence_result =
+            perform_calculation_per_sequence(opts, &prepared_seq, head, start, &mut rng)?;
 
-        let mut sequence_result = perform_calculation_per_sequence(opts, &prepared_seq, head);
+        let length = start + raw_seq.sequence.len();
+        lengths.insert(head.to_string(), length);
+        lengths.insert(format!("{}{}", head, REVERSED_SUFFIX), length);
 
         match sequence_result.remove(DIRECT) {
             Some(data) => result.insert(head.to_string(), data),
@@ -40,10 +65,10 @@ pub(crate) fn run_csv_cmd(opts: &CMDOptions) -> CMDResult {
         };
 
         match sequence_result.remove(INVERSE) {
-            Some(data) => result.insert(format!("{}-reversed", head).to_string(), data),
+            Some(data) => result.insert(format!("{}{}", head, REVERSED_SUFFIX).to_string(), data),
             None => None,
         };
     }
 
-    Ok(result)
+    Ok(CMDOutput { result, lengths })
 }
@@ -1,22 +1,42 @@
-use crate::cmd::{CMDOptions, CMDResult, DIRECT, INVERSE};
+use crate::cmd::{CMDOptions, CMDOutput, CMDResult, DIRECT, INVERSE};
 use crate::engine::calc::perform_calculation_per_sequence;
-use crate::engine::sequences::import_fasta_file;
+use crate::engine::sequences::{import_sequences, prepare_sequence};
+use crate::engine::task::REVERSED_SUFFIX;
+use aligner_core::get_rng;
 use std::collections::HashMap;
 
 pub(crate) fn run_exploring_cmd(opts: &CMDOptions) -> CMDResult {
     info!("Entering exploring mode!!");
 
-    let seqs = match import_fasta_file(opts.fasta_path.as_ref().unwrap().as_path()) {
+    let seqs = match import_sequences(
+        opts.fasta_path.as_ref().unwrap().as_path(),
+        opts.region.as_ref(),
+    ) {
         Ok(seqs) => seqs,
         Err(err) => return Err(err),
     };
 
     let mut result = HashMap::new();
+    let mut lengths = HashMap::new();
+    let mut rng = get_rng(opts.random_seed);
 
-    for raw_seq in seqs.iter() {
-        let head = std::str::from_utf8(&raw_seq.head).unwrap();
+    for raw_seq in seqs {
+        let (raw_seq, start) = raw_seq?;
+        let head = raw_seq.head();
+        let head = head.as_str();
 
-        let mut sequence_result = perform_calculation_per_sequence(opts, &raw_seq.seq, head);
+        let prepared_seq = prepare_sequence(
+            &raw_seq.sequence,
+            head,
+            raw_seq.feature_ranges(&opts.mask_features).into_iter(),
+        )?;
+
+        let mut sequence_result =
+            perform_calculation_per_sequence(opts, &prepared_seq, head, start, &mut rng)?;
+
+        let length = start + raw_seq.sequence.len();
+        lengths.insert(head.to_string(), length);
+        lengths.insert(format!("{}{}", head, REVERSED_SUFFIX), length);
 
         match sequence_result.remove(DIRECT) {
             Some(data) => result.insert(head.to_string(), data),
@@ -24,10 +44,10 @@ pub(crate) fn run_exploring_cmd(opts: &CMDOptions) -> CMDResult {
         };
 
         match sequence_result.remove(INVERSE) {
-            Some(data) => result.insert(format!("{}-reversed", head).to_string(), data),
+            Some(data) => result.insert(format!("{}{}", head, REVERSED_SUFFIX).to_string(), data),
             None => None,
         };
     }
 
-    Ok(result)
+    Ok(CMDOutput { result, lengths })
 }
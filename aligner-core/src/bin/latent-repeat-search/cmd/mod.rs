@@ -1,12 +1,17 @@
 use crate::cmd::csv::run_csv_cmd;
 use crate::cmd::exploring::run_exploring_cmd;
 use crate::cmd::testing::run_testing_cmd;
+use crate::engine::sequences::Region;
 use crate::engine::task::Task;
 use crate::error::Error;
 use crate::Args;
+use aligner_core::enums::Strand;
+use aligner_core::motif::{load_motifs, Motif, MotifFormat};
+use aligner_core::statistics::null_model::NullModel;
 use ndarray::Array2;
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 
 pub(crate) mod csv;
 pub(crate) mod exploring;
@@ -15,7 +20,13 @@ pub(crate) mod testing;
 const DIRECT: &str = "direct";
 const INVERSE: &str = "inverse";
 
-type CMDResult = Result<HashMap<String, (Vec<Task>, Array2<f64>)>, Error>;
+pub(crate) struct CMDOutput {
+    pub(crate) result: HashMap<String, (Vec<Task>, Array2<f64>)>,
+    // Lengths of the searched sequences in genome coordinates, keyed like result
+    pub(crate) lengths: HashMap<String, usize>,
+}
+
+type CMDResult = Result<CMDOutput, Error>;
 
 pub(crate) struct CMDOptions {
     pub(crate) repeat_length: usize,
@@ -38,6 +49,24 @@ pub(crate) struct CMDOptions {
 
     pub(crate) reverse: bool,
 
+    pub(crate) hmm: bool,
+
+    pub(crate) seed_motif: Option<Motif>,
+
+    pub(crate) random_seed: u64,
+
+    pub(crate) null_model: NullModel,
+
+    pub(crate) significance: f64,
+
+    pub(crate) dust: bool,
+
+    pub(crate) mask_features: Vec<String>,
+
+    pub(crate) mask_strand: Option<Strand>,
+
+    pub(crate) region: Option<Region>,
+
     pub(crate) testing: bool,
 
     pub(crate) csv: bool,
@@ -48,7 +77,7 @@ pub(crate) struct CMDOptions {
 }
 
 impl CMDOptions {
-    pub(crate) fn from_args(args: &Args) -> Self {
+    pub(crate) fn from_args(args: &Args) -> Result<Self, Error> {
         let mut testing = false;
         let mut csv = false;
 
@@ -68,7 +97,58 @@ impl CMDOptions {
             None => None,
         };
 
-        CMDOptions {
+        let seed_motif = match args.seed_motif.clone() {
+            Some(seed_path) => Some(load_seed_motif(
+                Path::new(&seed_path),
+                &args.seed_motif_format,
+                args.seed_motif_name.as_deref(),
+            )?),
+            None => None,
+        };
+
+        if let Some(motif) = &seed_motif {
+            let width = motif.counts.ncols();
+
+            if width != args.repeat_length {
+                warn!(
+                    "Seed motif {} is {} bases wide while --repeat-length is {}, repeats of {} bases will be searched",
+                    motif.name, width, args.repeat_length, width
+                );
+            }
+        }
+
+        let mask_strand = match args.mask_strand.as_deref() {
+            Some("+") => Some(Strand::Forward),
+            Some("-") => Some(Strand::Reverse),
+            Some(strand) => {
+                return Err(Error {
+                    msg: format!("unknown mask strand {}, + or - is expected", strand),
+                })
+            }
+            None => None,
+        };
+
+        let region = match &args.region {
+            Some(region) => Some(Region::from_str(region)?),
+            None => None,
+        };
+
+        let null_model = match NullModel::from_str(&args.null_model) {
+            Ok(null_model) => null_model,
+            Err(_) => {
+                return Err(Error {
+                    msg: format!("unknown null model {}", args.null_model),
+                })
+            }
+        };
+
+        if args.significance <= 0f64 || args.significance > 1f64 {
+            return Err(Error {
+                msg: format!("significance level {} is out of (0, 1]", args.significance),
+            });
+        }
+
+        Ok(CMDOptions {
             repeat_length: args.repeat_length,
             query_offset: args.query_offset,
             deletions: args.deletions,
@@ -79,11 +159,71 @@ impl CMDOptions {
             repeats: args.repeats,
             simple_init: args.simple_init,
             reverse: args.reverse,
+            hmm: args.hmm,
+            seed_motif,
+            random_seed: args.random_seed.unwrap_or_else(rand::random),
+            null_model,
+            significance: args.significance,
+            dust: args.dust,
+            mask_features: match &args.mask_features {
+                Some(kinds) => kinds
+                    .split(',')
+                    .map(|kind| kind.trim().to_string())
+                    .collect(),
+                None => vec![],
+            },
+            mask_strand,
+            region,
             testing,
             csv,
             fasta_path,
             csv_path,
+        })
+    }
+}
+
+fn load_seed_motif(path: &Path, format: &str, name: Option<&str>) -> Result<Motif, Error> {
+    let format = match MotifFormat::from_str(format) {
+        Ok(format) => format,
+        Err(_) => {
+            return Err(Error {
+                msg: format!("unknown seed motif format {}", format),
+            })
+        }
+    };
+
+    let mut motifs = match load_motifs(path, format) {
+        Ok(motifs) => motifs,
+        Err(err) => {
+            return Err(Error {
+                msg: format!("unable to load seed motif {}: {:?}", path.display(), err),
+            })
+        }
+    };
+
+    let position = match name {
+        Some(name) => motifs.iter().position(|motif| motif.name == name),
+        None => {
+            if motifs.len() > 1 {
+                warn!(
+                    "{} holds {} motifs, the first one is used as the seed",
+                    path.display(),
+                    motifs.len()
+                );
+            }
+
+            (!motifs.is_empty()).then_some(0)
         }
+    };
+
+    match (position, name) {
+        (Some(position), _) => Ok(motifs.swap_remove(position)),
+        (None, Some(name)) => Err(Error {
+            msg: format!("no seed motif {} in {}", name, path.display()),
+        }),
+        (None, None) => Err(Error {
+            msg: format!("no motifs in {}", path.display()),
+        }),
     }
 }
 
@@ -1,10 +1,11 @@
-use crate::cmd::{CMDOptions, CMDResult};
+use crate::cmd::{CMDOptions, CMDOutput, CMDResult};
 use crate::engine::calc::{calculate_cycle, calculate_starting_values};
 use crate::engine::generate_descendants;
+use crate::engine::scorer::Scorer;
 use crate::engine::MutationPercent::Quarter;
 use aligner_core::enums::{BioData, Index, DNA};
 use aligner_core::heuristic::HeuristicPWMAligner;
-use aligner_core::{get_random_pwm, AlignerTrait, Heuristics};
+use aligner_core::{get_random_pwm, get_rng, AlignerTrait, Heuristics};
 use std::collections::HashMap;
 
 const TEST_SEQUENCE_LENGTH: usize = 100000;
@@ -13,17 +14,19 @@ const DESCENDANTS_AMOUNT: usize = 10;
 pub(crate) fn run_testing_cmd(opts: &CMDOptions) -> CMDResult {
     info!("Entering testing mode!!");
 
+    let mut rng = get_rng(opts.random_seed);
+
     // testing chromosome
-    let sequence_raw = DNA::random_seq(TEST_SEQUENCE_LENGTH).unwrap();
+    let sequence_raw = DNA::random_seq(TEST_SEQUENCE_LENGTH, &mut rng).unwrap();
 
     // sample sequence to find
     let (query, freqs) =
-        DNA::random_seq_with_freqs(opts.repeat_length + opts.query_offset).unwrap();
+        DNA::random_seq_with_freqs(opts.repeat_length + opts.query_offset, &mut rng).unwrap();
 
     // find matrix for randomly generated matrix
     debug!("Finding matrix for sample sequence");
 
-    let mut matrix = get_random_pwm(opts.repeat_length);
+    let mut matrix = get_random_pwm::<DNA, _>(opts.repeat_length, &mut rng);
 
     let mut aligner = HeuristicPWMAligner::from_seqs(&query, &[]).unwrap();
     let result = aligner
@@ -44,7 +47,8 @@ pub(crate) fn run_testing_cmd(opts: &CMDOptions) -> CMDResult {
     // get descendants of sample sequence
     debug!("Getting descendants of sample sequence");
 
-    let mut descendants = generate_descendants(&query, DESCENDANTS_AMOUNT, Quarter).unwrap();
+    let mut descendants =
+        generate_descendants(&query, DESCENDANTS_AMOUNT, Quarter, &mut rng).unwrap();
 
     let offset = sequence_raw.len() / (descendants.len() + 1);
 
@@ -57,19 +61,23 @@ pub(crate) fn run_testing_cmd(opts: &CMDOptions) -> CMDResult {
     // try to find descendants in random sequence
     debug!("Searching for descendants");
 
-    let (mean, std) = calculate_starting_values(&sequence, &matrix, opts);
+    let scorer = Scorer::Matrix(matrix.clone());
+
+    let null = calculate_starting_values(&sequence, &scorer, opts, &mut rng)?;
 
     debug!("Calculating exactly one cycle");
 
     let mut result = HashMap::new();
+    let mut lengths = HashMap::new();
 
+    lengths.insert(String::from("test"), sequence.len());
     result.insert(
         String::from("test"),
         (
-            calculate_cycle(&sequence, &matrix, &Vec::<Index>::new(), mean, std, opts),
+            calculate_cycle(&sequence, &scorer, &Vec::<Index>::new(), &null, opts, 1)?,
             matrix,
         ),
     );
 
-    Ok(result)
+    Ok(CMDOutput { result, lengths })
 }
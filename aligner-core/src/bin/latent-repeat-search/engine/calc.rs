@@ -1,26 +1,36 @@
 use crate::cmd::CMDOptions;
+use crate::engine::scorer::Scorer;
 use crate::engine::task::Task;
 use crate::engine::{index_coord, rotate_indices};
+use crate::error::Error;
 use crate::filter;
+use aligner_core::alignment::PWMAlignment;
 use aligner_core::enums::{BioData, Index, DNA};
-use aligner_core::pwm::PWMAligner;
+use aligner_core::get_random_pwm;
+use aligner_core::hmm::ProfileHMM;
+use aligner_core::masking::{apply_mask, dust, DustOptions};
+use aligner_core::reader::twobit::range_to_bio_data;
+use aligner_core::statistics::composition::get_smoothed_composition;
+use aligner_core::statistics::evd::{calculate_q_values, GumbelParams};
 use aligner_core::AlignmentTrait;
-use aligner_core::{get_random_pwm, AlignerTrait};
 use aligner_helpers::matrices::transform_matrix;
 use ndarray::Array2;
-use rand::prelude::SliceRandom;
-use rand::thread_rng;
+use rand::Rng;
 use std::collections::HashMap;
-use std::sync::mpsc::channel;
 use std::thread;
 
-const Z: f64 = 3f64;
+// Added to every base of the profile HMM background
+const BACKGROUND_PSEUDOCOUNT: f64 = 1f64;
 
-pub(crate) fn calculate_starting_values(
+// Tasks and the final matrix of the direct and inverse searches
+pub(crate) type SequenceResult = HashMap<String, (Vec<Task>, Array2<f64>)>;
+
+pub(crate) fn calculate_starting_values<R: Rng + ?Sized>(
     query: &[DNA],
-    matrix: &Array2<f64>,
+    scorer: &Scorer,
     opts: &CMDOptions,
-) -> (f64, f64) {
+    rng: &mut R,
+) -> Result<GumbelParams, Error> {
     let length = query.len();
     let query_offset = opts.query_offset;
     let threads = opts.threads;
@@ -28,71 +38,73 @@ pub(crate) fn calculate_starting_values(
     let deletions = opts.deletions;
     let extension = opts.extension;
 
-    let mut shuffled_query = Vec::from(query);
-    let mut thread_rng = thread_rng();
-    shuffled_query.shuffle(&mut thread_rng);
+    let shuffled_query = opts.null_model.generate(query, rng);
 
     let mut fs = vec![];
 
+    // Sequences under 1000 bases still get a window at every offset
     let step = if opts.simple_init {
-        query.len() / 1000
+        (query.len() / 1000).max(1)
     } else {
         opts.query_offset
     };
 
     info!("Calculating starting values");
 
-    let (tx, rx) = channel();
-
-    for i in 0..opts.threads {
-        let tx = tx.clone();
-        let shuffled_query = shuffled_query.clone();
-        let matrix = matrix.clone();
-
-        thread::spawn(move || {
-            for j in (i * query_offset..length).step_by(step * threads) {
-                let border = if j + repeat_length + query_offset >= length {
-                    length
-                } else {
-                    j + repeat_length + query_offset
-                };
-
-                let result = PWMAligner::<DNA>::from_seqs(&shuffled_query[j..border], &[])
-                    .unwrap()
-                    .perform_alignment(deletions, extension, &matrix, None)
-                    .unwrap();
+    // Every thread keeps its own scores and they are joined in thread order, so
+    // the fit doesn't depend on thread scheduling
+    let received: Vec<Vec<Result<f64, Error>>> = thread::scope(|scope| {
+        let handles: Vec<_> = (0..threads)
+            .map(|i| {
+                let shuffled_query = &shuffled_query;
+
+                scope.spawn(move || {
+                    (i * query_offset..length)
+                        .step_by(step * threads)
+                        .map(|j| {
+                            let border = if j + repeat_length + query_offset >= length {
+                                length
+                            } else {
+                                j + repeat_length + query_offset
+                            };
+
+                            scorer
+                                .align(&shuffled_query[j..border], deletions, extension)
+                                .map(|alignment| alignment.f)
+                        })
+                        .collect()
+                })
+            })
+            .collect();
 
-                tx.send(result.alignment.f).unwrap();
-            }
-            drop(tx);
-        });
-    }
-    drop(tx);
+        handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect()
+    });
 
-    while let Ok(f) = rx.recv() {
-        fs.push(f);
+    for f in received.into_iter().flatten() {
+        fs.push(f?);
     }
 
-    let mean = fs.iter().sum::<f64>() / fs.len() as f64;
-
-    (
-        mean,
-        (fs.iter()
-            .map(|value| (value - mean) * (value - mean))
-            .sum::<f64>()
-            / fs.len() as f64)
-            .sqrt(),
-    )
+    // Too few windows or windows that all score the same have no distribution
+    GumbelParams::fit(&fs).map_err(|err| Error {
+        msg: format!(
+            "unable to fit the null distribution to {} window scores: {:?}",
+            fs.len(),
+            err
+        ),
+    })
 }
 
 pub(crate) fn calculate_cycle(
     query: &[DNA],
-    matrix: &Array2<f64>,
+    scorer: &Scorer,
     indices: &[Index],
-    mean: f64,
-    std: f64,
+    null: &GumbelParams,
     opts: &CMDOptions,
-) -> Vec<Task> {
+    cycle: usize,
+) -> Result<Vec<Task>, Error> {
     let length = query.len();
     let query_offset = opts.query_offset;
     let threads = opts.threads;
@@ -100,60 +112,94 @@ pub(crate) fn calculate_cycle(
     let deletions = opts.deletions;
     let extension = opts.extension;
 
+    let mean = null.mean();
+    let std = null.std();
+
     let mut tasks = vec![];
 
-    let (tx, rx) = channel();
+    // Tasks are joined in thread order, as in calculate_starting_values
+    let received: Vec<Vec<Result<Task, Error>>> = thread::scope(|scope| {
+        let handles: Vec<_> = (0..threads)
+            .map(|i| {
+                scope.spawn(move || {
+                    (i * query_offset..length)
+                        .step_by(query_offset * threads)
+                        .map(|j| {
+                            let border = if j + repeat_length + query_offset >= length {
+                                length
+                            } else {
+                                j + repeat_length + query_offset
+                            };
+
+                            scorer
+                                .align(&query[j..border], deletions, extension)
+                                .map(|alignment| Task {
+                                    left_coord: index_coord(j, indices),
+                                    right_coord: index_coord(border, indices),
+                                    cycle,
+                                    z: (alignment.f - mean) / std,
+                                    p_value: null.get_p_value(alignment.f),
+                                    q_value: 1f64,
+                                    alignment,
+                                })
+                        })
+                        .collect()
+                })
+            })
+            .collect();
 
-    for i in 0..opts.threads {
-        let tx = tx.clone();
-        let query = Vec::from(query);
-        let matrix = matrix.clone();
-        let indices = Vec::from(indices);
+        handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect()
+    });
 
-        thread::spawn(move || {
-            for j in (i * query_offset..length).step_by(query_offset * threads) {
-                let border = if j + repeat_length + query_offset >= length {
-                    length
-                } else {
-                    j + repeat_length + query_offset
-                };
+    for task in received.into_iter().flatten() {
+        tasks.push(task?);
+    }
 
-                let result = PWMAligner::<DNA>::from_seqs(&query[j..border], &[])
-                    .unwrap()
-                    .perform_alignment(deletions, extension, &matrix, None)
-                    .unwrap();
+    let p_values: Vec<f64> = tasks.iter().map(|task| task.p_value).collect();
 
-                tx.send(Task {
-                    alignment: result.alignment.clone(),
-                    left_coord: index_coord(j, &indices),
-                    right_coord: index_coord(border, &indices),
-                    z: (result.alignment.f - mean) / std,
-                })
-                .unwrap();
-            }
-            drop(tx);
-        });
+    for (task, q_value) in tasks.iter_mut().zip(calculate_q_values(&p_values)) {
+        task.q_value = q_value;
     }
-    drop(tx);
 
-    while let Ok(task) = rx.recv() {
-        if task.z >= Z {
-            debug!("{:?}", task);
-            tasks.push(task);
-        }
+    tasks.retain(|task| task.q_value <= opts.significance);
+
+    for task in tasks.iter() {
+        debug!("{:?}", task);
     }
 
-    tasks
+    Ok(tasks)
 }
 
-pub(crate) fn perform_calculation_per_sequence(
+pub(crate) fn perform_calculation_per_sequence<R: Rng + ?Sized>(
     opts: &CMDOptions,
     raw_seq: &[u8],
     head: &str,
-) -> HashMap<String, (Vec<Task>, Array2<f64>)> {
-    let (mut query, frequencies, indices) =
-        DNA::from_u8_vec_with_freqs_and_indices(raw_seq).unwrap();
-    let mut matrix = get_random_pwm(opts.repeat_length);
+    start: usize,
+    rng: &mut R,
+) -> Result<SequenceResult, Error> {
+    let raw_seq = if opts.dust {
+        let (sequence, _, _) = DNA::from_u8_vec_with_freqs_and_indices(raw_seq).unwrap();
+        let mask = dust(&sequence, &DustOptions::default());
+
+        info!(
+            "Masked {} low-complexity positions in \"{}\"",
+            mask.iter().filter(|elem| **elem).count(),
+            head
+        );
+
+        apply_mask::<DNA>(raw_seq, &mask, b'N')
+    } else {
+        Vec::from(raw_seq)
+    };
+
+    let (mut query, frequencies, indices) = range_to_bio_data::<DNA>(&raw_seq, start).unwrap();
+    let mut matrix = match &opts.seed_motif {
+        Some(motif) => motif.counts.clone(),
+        None => get_random_pwm::<DNA, _>(opts.repeat_length, rng),
+    };
 
     matrix = transform_matrix(
         &matrix,
@@ -171,36 +217,39 @@ pub(crate) fn perform_calculation_per_sequence(
 
     info!("Calculating direct of {}", head);
 
-    let (mut mean, mut std) = calculate_starting_values(&query, &matrix, opts);
+    let mut scorer = Scorer::Matrix(matrix.clone());
 
-    info!("Calculated starting mean={} and std={}", mean, std);
+    let mut null = match calculate_starting_values(&query, &scorer, opts, rng) {
+        Ok(null) => null,
+        Err(err) => {
+            warn!("Skipping \"{}\": {}", head, err.msg);
+            return Ok(HashMap::new());
+        }
+    };
+
+    info!("Calculated starting mu={} and beta={}", null.mu, null.beta);
 
     let mut result = HashMap::new();
 
     let mut tasks = vec![];
+    let mut cycles = 0;
 
     for i in 0..opts.repeats {
         info!("Calculating cycle {}", i + 1);
 
-        info!("mean={} and std={} for this cycle", mean, std);
+        info!("mu={} and beta={} for this cycle", null.mu, null.beta);
 
-        let new_tasks = calculate_cycle(&query, &matrix, &indices, mean, std, opts);
+        let new_tasks = calculate_cycle(&query, &scorer, &indices, &null, opts, i + 1)?;
 
         if new_tasks.is_empty() {
             break;
         }
         tasks = filter(new_tasks).unwrap();
+        cycles = i + 1;
 
         info!("Tasks are filtered");
 
         if i < opts.repeats - 1 {
-            mean = tasks.iter().map(|value| value.alignment.f).sum::<f64>() / tasks.len() as f64;
-            std = tasks
-                .iter()
-                .map(|value| (value.alignment.f - mean) * (value.alignment.f - mean))
-                .sum::<f64>()
-                / tasks.len() as f64;
-
             matrix = Array2::<f64>::zeros((matrix.shape()[0], matrix.shape()[1]));
             for task in tasks.iter() {
                 matrix = matrix + task.alignment.get_frequency_matrix();
@@ -213,11 +262,50 @@ pub(crate) fn perform_calculation_per_sequence(
                 &frequencies,
             )
             .unwrap();
+
+            let next_scorer = if opts.hmm {
+                let alignments: Vec<PWMAlignment<DNA>> =
+                    tasks.iter().map(|task| task.alignment.clone()).collect();
+
+                // Short, AT-only or masked sequences may lack some bases, which
+                // the HMM can't have in its background
+                let background = get_smoothed_composition(&query, BACKGROUND_PSEUDOCOUNT);
+
+                let hmm = match ProfileHMM::from_alignments(&alignments, &background) {
+                    Ok(hmm) => hmm,
+                    Err(err) => {
+                        return Err(Error {
+                            msg: format!("unable to build a profile HMM for {}: {:?}", head, err),
+                        })
+                    }
+                };
+
+                info!("Profile HMM rebuilt");
+
+                Scorer::Profile(hmm)
+            } else {
+                Scorer::Matrix(matrix.clone())
+            };
+
+            // The scorer and its null are only replaced together, so the
+            // inverse search still has a matching pair after a failed fit
+            match calculate_starting_values(&query, &next_scorer, opts, rng) {
+                Ok(next_null) => {
+                    scorer = next_scorer;
+                    null = next_null;
+                }
+                Err(err) => {
+                    warn!("Stopping \"{}\" after cycle {}: {}", head, i + 1, err.msg);
+                    break;
+                }
+            }
         }
 
         info!("Calculated cycle {}", i + 1);
     }
 
+    // The PWM of the last hits is kept even with --hmm, the HMM is only
+    // used for scoring and isn't exported
     result.insert("direct".to_string(), (tasks, matrix.clone()));
 
     if opts.reverse {
@@ -228,7 +316,7 @@ pub(crate) fn perform_calculation_per_sequence(
         let rotated_indices = rotate_indices(&indices, query.len());
 
         let mut tasks_inverted =
-            calculate_cycle(&query, &matrix, &rotated_indices, mean, std, opts);
+            calculate_cycle(&query, &scorer, &rotated_indices, &null, opts, cycles)?;
 
         tasks_inverted = filter(tasks_inverted).unwrap();
 
@@ -237,5 +325,5 @@ pub(crate) fn perform_calculation_per_sequence(
         result.insert("inverse".to_string(), (tasks_inverted, matrix));
     }
 
-    result
+    Ok(result)
 }
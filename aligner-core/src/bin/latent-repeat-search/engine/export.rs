@@ -0,0 +1,199 @@
+use crate::cmd::CMDOptions;
+use crate::engine::sequences::import_sequences;
+use crate::engine::task::{get_family_name, Task, REVERSED_SUFFIX};
+use crate::Error;
+use aligner_core::reader::SequenceRecord;
+use ndarray::Array2;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+const N: u8 = 78;
+
+// A found copy in the forward coordinates of its sequence, numbered like the
+// IDs of GFF3 output
+pub(crate) struct RepeatCopy {
+    pub(crate) left: usize,
+    pub(crate) right: usize,
+    pub(crate) reversed: bool,
+    pub(crate) family: String,
+    pub(crate) number: usize,
+}
+
+// Copies of every family grouped by the header of the sequence they are in
+pub(crate) fn get_copies(
+    result: &HashMap<String, (Vec<Task>, Array2<f64>)>,
+    lengths: &HashMap<String, usize>,
+) -> HashMap<String, Vec<RepeatCopy>> {
+    let mut copies = HashMap::<String, Vec<RepeatCopy>>::new();
+
+    for (key, (tasks, _)) in result.iter() {
+        let head = key.strip_suffix(REVERSED_SUFFIX).unwrap_or(key);
+
+        for (i, task) in tasks.iter().enumerate() {
+            let (left, right, reversed) = task.forward_coords(key, lengths[key]);
+
+            copies
+                .entry(head.to_string())
+                .or_default()
+                .push(RepeatCopy {
+                    left,
+                    right,
+                    reversed,
+                    family: get_family_name(key),
+                    number: i + 1,
+                });
+        }
+    }
+
+    copies
+}
+
+fn create(path: Option<&Path>) -> Result<Option<BufWriter<File>>, Error> {
+    match path.map(File::create) {
+        Some(Ok(file)) => Ok(Some(BufWriter::new(file))),
+        Some(Err(err)) => Err(Error {
+            msg: format!("unable to create {}: {}", path.unwrap().display(), err),
+        }),
+        None => Ok(None),
+    }
+}
+
+fn write(writer: &mut BufWriter<File>, record: &SequenceRecord) -> Result<(), Error> {
+    match writer.write_all(record.to_fasta().as_bytes()) {
+        Ok(_) => Ok(()),
+        Err(err) => Err(Error {
+            msg: format!("unable to write {}: {}", record.id, err),
+        }),
+    }
+}
+
+// Every copy of a record as a separate record. Headers hold 1-based inclusive
+// coordinates, and copies of the inverse search are taken from the forward
+// strand as they are and marked like in GFF3 output, since the search only
+// reverses the sequence
+pub(crate) fn get_copy_records(
+    raw_seq: &SequenceRecord,
+    start: usize,
+    copies: &[RepeatCopy],
+) -> Vec<SequenceRecord> {
+    let mut records = vec![];
+
+    for copy in copies.iter() {
+        let range = copy.left.saturating_sub(start)..copy.right.saturating_sub(start);
+        let sequence = match raw_seq.sequence.get(range) {
+            Some(sequence) => sequence.to_vec(),
+            None => {
+                warn!(
+                    "Copy {}.{} is out of \"{}\"",
+                    copy.family,
+                    copy.number,
+                    raw_seq.head()
+                );
+                continue;
+            }
+        };
+
+        let location = format!("{}:{}-{}", raw_seq.id, copy.left + 1, copy.right);
+
+        records.push(SequenceRecord {
+            id: format!("{}.{}", copy.family, copy.number),
+            description: Some(match copy.reversed {
+                true => format!("{} orientation=reversed", location),
+                false => format!("{}(+)", location),
+            }),
+            sequence,
+            quality: None,
+            features: vec![],
+        });
+    }
+
+    records
+}
+
+// Hard (N) or soft (lowercase) masks the copies of a record
+pub(crate) fn mask_copies(
+    raw_seq: &mut SequenceRecord,
+    start: usize,
+    copies: &[RepeatCopy],
+    soft_mask: bool,
+) {
+    let length = raw_seq.sequence.len();
+
+    for copy in copies.iter() {
+        let (left, right) = (
+            copy.left.saturating_sub(start),
+            copy.right.saturating_sub(start),
+        );
+
+        // Copies running past the record are masked up to its end
+        if right > length {
+            warn!(
+                "Copy {}.{} is out of \"{}\"",
+                copy.family,
+                copy.number,
+                raw_seq.head()
+            );
+        }
+
+        if let Some(elems) = raw_seq
+            .sequence
+            .get_mut(left.min(length)..right.min(length))
+        {
+            for elem in elems.iter_mut() {
+                *elem = match soft_mask {
+                    true => elem.to_ascii_lowercase(),
+                    false => N,
+                };
+            }
+        }
+    }
+}
+
+// Reads the input once more and writes it with the copies masked, and every
+// copy as a separate record
+pub(crate) fn export_sequences(
+    opts: &CMDOptions,
+    copies: &HashMap<String, Vec<RepeatCopy>>,
+    masked_path: Option<&Path>,
+    soft_mask: bool,
+    copies_path: Option<&Path>,
+) -> Result<(), Error> {
+    let mut masked_writer = create(masked_path)?;
+    let mut copies_writer = create(copies_path)?;
+
+    let seqs = import_sequences(
+        opts.fasta_path.as_ref().unwrap().as_path(),
+        opts.region.as_ref(),
+    )?;
+
+    for raw_seq in seqs {
+        let (mut raw_seq, start) = raw_seq?;
+        let sequence_copies = match copies.get(&raw_seq.head()) {
+            Some(sequence_copies) => sequence_copies.as_slice(),
+            None => &[],
+        };
+
+        if let Some(writer) = copies_writer.as_mut() {
+            for record in get_copy_records(&raw_seq, start, sequence_copies).iter() {
+                write(writer, record)?;
+            }
+        }
+
+        if let Some(writer) = masked_writer.as_mut() {
+            mask_copies(&mut raw_seq, start, sequence_copies, soft_mask);
+            write(writer, &raw_seq)?;
+        }
+    }
+
+    for writer in [masked_writer, copies_writer].iter_mut().flatten() {
+        if let Err(err) = writer.flush() {
+            return Err(Error {
+                msg: format!("unable to write FASTA: {}", err),
+            });
+        }
+    }
+
+    Ok(())
+}
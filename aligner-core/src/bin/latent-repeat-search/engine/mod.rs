@@ -1,11 +1,14 @@
 use crate::engine::task::Task;
 use aligner_core::enums::{BioData, Index};
 use aligner_core::Result;
-use rand::{thread_rng, Rng};
+use rand::Rng;
 
 pub(crate) mod calc;
+pub(crate) mod export;
+pub(crate) mod scorer;
 pub(crate) mod sequences;
 pub(crate) mod task;
+#[cfg(test)]
 mod test;
 
 #[allow(dead_code)]
@@ -14,33 +17,33 @@ pub(crate) enum MutationPercent {
     Half = 2,
 }
 
-pub(crate) fn generate_descendants<T: BioData + From<usize> + Clone>(
+pub(crate) fn generate_descendants<T: BioData + From<usize> + Clone, R: Rng + ?Sized>(
     sequence: &[T],
     amount: usize,
     percent: MutationPercent,
+    rng: &mut R,
 ) -> Result<Vec<Vec<T>>> {
     let mut result = vec![];
 
     let offset = percent as usize;
 
     for i in 0..amount {
-        result.push(mutate(sequence, offset, i).unwrap())
+        result.push(mutate(sequence, offset, i, rng).unwrap())
     }
 
     Ok(result)
 }
 
-fn mutate<T: BioData + From<usize> + Clone>(
+fn mutate<T: BioData + From<usize> + Clone, R: Rng + ?Sized>(
     sequence: &[T],
     offset: usize,
     start: usize,
+    rng: &mut R,
 ) -> Result<Vec<T>> {
     let mut result = Vec::from(sequence);
 
-    let mut thread_rng = thread_rng();
-
     for i in (start..sequence.len()).step_by(offset) {
-        result[i] = thread_rng.gen_range(0..T::volume()).into()
+        result[i] = rng.gen_range(0..T::volume()).into()
     }
 
     Ok(result)
@@ -0,0 +1,39 @@
+use crate::error::Error;
+use aligner_core::alignment::PWMAlignment;
+use aligner_core::enums::DNA;
+use aligner_core::hmm::ProfileHMM;
+use aligner_core::pwm::PWMAligner;
+use aligner_core::AlignerTrait;
+use ndarray::Array2;
+
+#[derive(Debug, Clone)]
+pub(crate) enum Scorer {
+    Matrix(Array2<f64>),
+    Profile(ProfileHMM<DNA>),
+}
+
+impl Scorer {
+    pub(crate) fn align(
+        &self,
+        query: &[DNA],
+        deletions: f64,
+        extension: f64,
+    ) -> Result<PWMAlignment<DNA>, Error> {
+        let result = match self {
+            Scorer::Matrix(matrix) => PWMAligner::<DNA>::from_seqs(query, &[])
+                .and_then(|mut aligner| {
+                    aligner.perform_alignment(deletions, extension, matrix, None)
+                })
+                .map(|result| result.alignment),
+            Scorer::Profile(hmm) => hmm.align(query),
+        };
+
+        result.map_err(|err| Error {
+            msg: format!(
+                "unable to score a window of {} bases: {:?}",
+                query.len(),
+                err
+            ),
+        })
+    }
+}
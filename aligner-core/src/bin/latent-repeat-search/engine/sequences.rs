@@ -1,43 +1,245 @@
 use crate::Error;
-use aligner_helpers::csv::Record;
-use aligner_helpers::files::load_file_contents;
-use seq_io::fasta::Reader;
+use aligner_core::annotation::{load_annotations, AnnotationFormat};
+use aligner_core::enums::Strand;
+use aligner_core::reader::twobit::TwoBitReader;
+use aligner_core::reader::{SequenceReader, SequenceRecord};
+use aligner_helpers::csv::read_csv;
+use std::collections::HashMap;
 use std::path::Path;
+use std::str::FromStr;
 
 const N: u8 = 78;
+const TWO_BIT_EXTENSION: &str = "2bit";
 
-pub(crate) fn import_fasta_file(path: &Path) -> Result<Vec<seq_io::fasta::OwnedRecord>, Error> {
-    let contents = load_file_contents(path);
-    let bytes = contents.as_bytes();
+// Known repeats per sequence name as half-open ranges
+pub(crate) type Masks = HashMap<String, Vec<(usize, usize)>>;
 
-    let mut reader = Reader::new(bytes);
+// Records paired with the genome coordinate of their first base
+pub(crate) type Sequences = Box<dyn Iterator<Item = Result<(SequenceRecord, usize), Error>>>;
 
-    let seqs: Vec<_> = match reader.records().collect() {
-        Ok(seqs) => seqs,
+// A sequence name with an optional 0-based half-open range, e.g. chr1:1000-2000
+#[derive(Debug, Clone)]
+pub(crate) struct Region {
+    pub(crate) name: String,
+    pub(crate) range: Option<(usize, usize)>,
+}
+
+impl FromStr for Region {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, range) = match s.rsplit_once(':') {
+            Some((name, range)) => (name, Some(range)),
+            None => (s, None),
+        };
+
+        let range = match range.map(|range| range.split_once('-')) {
+            Some(Some((start, end))) => match (start.parse(), end.parse()) {
+                (Ok(start), Ok(end)) if start < end => Some((start, end)),
+                _ => None,
+            },
+            Some(None) => None,
+            None => {
+                return Ok(Region {
+                    name: name.to_string(),
+                    range: None,
+                })
+            }
+        };
+
+        match range {
+            Some(range) if !name.is_empty() => Ok(Region {
+                name: name.to_string(),
+                range: Some(range),
+            }),
+            _ => Err(Error {
+                msg: format!("malformed region {}", s),
+            }),
+        }
+    }
+}
+
+pub(crate) fn import_sequences(path: &Path, region: Option<&Region>) -> Result<Sequences, Error> {
+    if path
+        .extension()
+        .is_some_and(|extension| extension == TWO_BIT_EXTENSION)
+    {
+        return import_two_bit_file(path, region);
+    }
+
+    if region.is_some() {
+        return Err(Error {
+            msg: "regions are supported for .2bit input only".to_string(),
+        });
+    }
+
+    let records = import_fasta_file(path)?;
+
+    Ok(Box::new(
+        records.map(|record| record.map(|record| (record, 0))),
+    ))
+}
+
+// Only the requested ranges are decoded, whole sequences one by one otherwise
+fn import_two_bit_file(path: &Path, region: Option<&Region>) -> Result<Sequences, Error> {
+    let display = path.display().to_string();
+    let to_error = move |err| Error {
+        msg: format!("unable to read {}: {:?}", display, err),
+    };
+
+    let mut reader = TwoBitReader::from_path(path).map_err(to_error.clone())?;
+
+    let ranges = match region {
+        Some(region) => vec![(region.name.clone(), region.range)],
+        None => reader
+            .names()
+            .into_iter()
+            .map(|name| (name, None))
+            .collect(),
+    };
+
+    if ranges.is_empty() {
+        return Err(Error {
+            msg: "empty 2bit file".to_string(),
+        });
+    }
+
+    Ok(Box::new(ranges.into_iter().map(move |(name, range)| {
+        let (start, end) = match range {
+            Some(range) => range,
+            None => (0, reader.length(&name).map_err(to_error.clone())?),
+        };
+
+        let sequence = reader
+            .read_range(&name, start, end)
+            .map_err(to_error.clone())?;
+
+        Ok((
+            SequenceRecord {
+                id: name,
+                description: None,
+                sequence,
+                quality: None,
+                features: vec![],
+            },
+            start,
+        ))
+    })))
+}
+
+// Records are read lazily, so only one chromosome is kept in memory at a time
+fn import_fasta_file(
+    path: &Path,
+) -> Result<impl Iterator<Item = Result<SequenceRecord, Error>>, Error> {
+    let mut reader = match SequenceReader::from_path(path) {
+        Ok(reader) => reader.peekable(),
         Err(err) => {
             return Err(Error {
-                msg: err.to_string(),
+                msg: format!("unable to open {}: {:?}", path.display(), err),
             })
         }
     };
 
-    if seqs.is_empty() {
+    if reader.peek().is_none() {
         return Err(Error {
             msg: "empty fasta file".to_string(),
         });
     }
 
-    Ok(seqs)
+    let display = path.display().to_string();
+
+    Ok(reader.map(move |record| {
+        record.map_err(|err| Error {
+            msg: format!("unable to read {}: {:?}", display, err),
+        })
+    }))
 }
 
-pub(crate) fn prepare_sequence(raw_seq: &[u8], data: &[Record]) -> Vec<u8> {
+// Known repeats from a CSV of a previous run, or BED and GFF3 annotations
+// (e.g. RepeatMasker or Dfam) filtered by type or name and by strand
+pub(crate) fn import_masks(
+    path: &Path,
+    kinds: &[String],
+    strand: Option<Strand>,
+) -> Result<Masks, Error> {
+    let mut result = Masks::new();
+
+    let format = match AnnotationFormat::from_path(path) {
+        Some(format) => format,
+        None => {
+            let data = match read_csv(path) {
+                Ok(data) => data,
+                Err(err) => {
+                    return Err(Error {
+                        msg: err.to_string(),
+                    })
+                }
+            };
+
+            for (name, records) in data.into_iter() {
+                let ranges = records
+                    .iter()
+                    .map(|record| (record.left_coord, record.right_coord))
+                    .collect();
+                result.insert(name, ranges);
+            }
+
+            return Ok(result);
+        }
+    };
+
+    let annotations = match load_annotations(path, format) {
+        Ok(annotations) => annotations,
+        Err(err) => {
+            return Err(Error {
+                msg: format!("unable to read {}: {:?}", path.display(), err),
+            })
+        }
+    };
+
+    for annotation in annotations.into_iter() {
+        if !kinds.is_empty() && !annotation.is_one_of(kinds) {
+            continue;
+        }
+
+        if strand.is_some() && annotation.strand != strand {
+            continue;
+        }
+
+        result
+            .entry(annotation.seqid)
+            .or_default()
+            .push((annotation.start, annotation.end));
+    }
+
+    Ok(result)
+}
+
+// Masks known repeats given as half-open ranges of raw coordinates
+pub(crate) fn prepare_sequence(
+    raw_seq: &[u8],
+    head: &str,
+    ranges: impl Iterator<Item = (usize, usize)>,
+) -> Result<Vec<u8>, Error> {
     let mut result = Vec::from(raw_seq);
 
-    for record in data.iter() {
-        for elem in result[record.left_coord..record.right_coord].iter_mut() {
+    for (left, right) in ranges {
+        if left > right || right > result.len() {
+            return Err(Error {
+                msg: format!(
+                    "mask {}..{} is out of \"{}\" with length {}",
+                    left,
+                    right,
+                    head,
+                    result.len()
+                ),
+            });
+        }
+
+        for elem in result[left..right].iter_mut() {
             *elem = N
         }
     }
 
-    result
+    Ok(result)
 }
@@ -1,12 +1,100 @@
 use aligner_core::alignment::PWMAlignment;
-use aligner_core::enums::DNA;
+use aligner_core::annotation::Annotation;
+use aligner_core::enums::{Strand, DNA};
+use aligner_core::AlignmentTrait;
+use ndarray::Array2;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone)]
+pub(crate) const REVERSED_SUFFIX: &str = "-reversed";
+const SOURCE: &str = "latent-repeat-search";
+const REPEAT_KIND: &str = "repeat_region";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub(crate) struct Task {
     pub(crate) alignment: PWMAlignment<DNA>,
     pub(crate) left_coord: usize,
     pub(crate) right_coord: usize,
     pub(crate) z: f64,
+    pub(crate) p_value: f64,
+    pub(crate) q_value: f64,
+    // Search cycle the copy was found in
+    #[serde(default)]
+    pub(crate) cycle: usize,
+}
+
+// Families are named after the sequence (and direction) they were found in
+pub(crate) fn get_family_name(key: &str) -> String {
+    key.split_whitespace().collect::<Vec<&str>>().join("_")
+}
+
+impl Task {
+    // Half-open coordinates on the forward strand of a sequence keyed like the
+    // results, and whether the copy was found in the reversed sequence. Inverse
+    // coordinates are counted from the end of the sequence, so they are
+    // flipped with its full length
+    pub(crate) fn forward_coords(&self, key: &str, length: usize) -> (usize, usize, bool) {
+        match key.ends_with(REVERSED_SUFFIX) {
+            true => (
+                length.saturating_sub(self.right_coord),
+                length.saturating_sub(self.left_coord),
+                true,
+            ),
+            false => (self.left_coord, self.right_coord, false),
+        }
+    }
+
+    // Copy of the family as a genome browser feature
+    pub(crate) fn to_annotation(
+        &self,
+        key: &str,
+        number: usize,
+        matrix: &Array2<f64>,
+        length: usize,
+    ) -> Annotation {
+        let head = key.strip_suffix(REVERSED_SUFFIX).unwrap_or(key);
+        let (start, end, reversed) = self.forward_coords(key, length);
+        let family = get_family_name(key);
+
+        let stats = self
+            .alignment
+            .get_stats(matrix, end - start, self.alignment.dim());
+
+        let mut attributes = vec![
+            ("ID".to_string(), format!("{}.{}", family, number)),
+            ("Name".to_string(), family.clone()),
+            ("family".to_string(), family.clone()),
+            ("cycle".to_string(), self.cycle.to_string()),
+            (
+                "matched_columns".to_string(),
+                (stats.aligned_length - stats.gaps).to_string(),
+            ),
+            ("identity".to_string(), format!("{:.4}", stats.identity)),
+            ("z".to_string(), format!("{:.4}", self.z)),
+            ("p_value".to_string(), format!("{:e}", self.p_value)),
+            ("q_value".to_string(), format!("{:e}", self.q_value)),
+        ];
+
+        // The inverse search reverses the sequence without complementing it, so
+        // its copies are on no strand
+        if reversed {
+            attributes.push(("orientation".to_string(), "reversed".to_string()));
+        }
+
+        Annotation {
+            seqid: head.split_whitespace().next().unwrap_or(head).to_string(),
+            source: Some(SOURCE.to_string()),
+            kind: Some(REPEAT_KIND.to_string()),
+            name: Some(family),
+            start,
+            end,
+            score: Some(self.alignment.f),
+            strand: match reversed {
+                true => None,
+                false => Some(Strand::Forward),
+            },
+            attributes,
+        }
+    }
 }
 
 impl PartialEq for Task {
@@ -1,6 +1,17 @@
+use crate::args::Args;
+use crate::cmd::CMDOptions;
+use crate::engine::calc::{calculate_starting_values, perform_calculation_per_sequence};
+use crate::engine::export::{get_copies, get_copy_records, mask_copies, RepeatCopy};
+use crate::engine::scorer::Scorer;
 use crate::engine::task::Task;
 use crate::filter;
 use aligner_core::alignment::PWMAlignment;
+use aligner_core::enums::{BioData, Strand, DNA};
+use aligner_core::get_rng;
+use aligner_core::reader::SequenceRecord;
+use clap::Parser;
+use ndarray::Array2;
+use std::collections::HashMap;
 
 #[test]
 fn filter_test() {
@@ -10,32 +21,47 @@ fn filter_test() {
         Task {
             alignment: alignment.clone(),
             z: 12.240966,
+            p_value: 0f64,
+            q_value: 0f64,
             left_coord: 300,
             right_coord: 630,
+            cycle: 1,
         },
         Task {
             alignment: alignment.clone(),
             z: 12.378159,
+            p_value: 0f64,
+            q_value: 0f64,
             left_coord: 360,
             right_coord: 690,
+            cycle: 1,
         },
         Task {
             alignment: alignment.clone(),
             z: 11.762683,
+            p_value: 0f64,
+            q_value: 0f64,
             left_coord: 1080,
             right_coord: 1410,
+            cycle: 1,
         },
         Task {
             alignment: alignment.clone(),
             z: 10.471823,
+            p_value: 0f64,
+            q_value: 0f64,
             left_coord: 1740,
             right_coord: 2070,
+            cycle: 1,
         },
         Task {
             alignment: alignment.clone(),
             z: 11.392030,
+            p_value: 0f64,
+            q_value: 0f64,
             left_coord: 1860,
             right_coord: 2190,
+            cycle: 1,
         },
     ];
 
@@ -43,22 +69,201 @@ fn filter_test() {
         Task {
             alignment: alignment.clone(),
             z: 12.378159,
+            p_value: 0f64,
+            q_value: 0f64,
             left_coord: 360,
             right_coord: 690,
+            cycle: 1,
         },
         Task {
             alignment: alignment.clone(),
             z: 11.762683,
+            p_value: 0f64,
+            q_value: 0f64,
             left_coord: 1080,
             right_coord: 1410,
+            cycle: 1,
         },
         Task {
             alignment,
             z: 11.392030,
+            p_value: 0f64,
+            q_value: 0f64,
             left_coord: 1860,
             right_coord: 2190,
+            cycle: 1,
         },
     ];
 
     assert_eq!(filter(tasks).unwrap(), expected_result)
 }
+
+#[test]
+fn task_to_annotation_test() {
+    let task = Task {
+        alignment: PWMAlignment::empty(),
+        z: 11.5,
+        p_value: 0f64,
+        q_value: 0f64,
+        left_coord: 100,
+        right_coord: 160,
+        cycle: 2,
+    };
+    let matrix = Array2::<f64>::zeros((4, 1));
+
+    let direct = task.to_annotation("chr1 sample", 1, &matrix, 1000);
+    assert_eq!(direct.seqid, "chr1");
+    assert_eq!((direct.start, direct.end), (100, 160));
+    assert_eq!(direct.strand, Some(Strand::Forward));
+    assert_eq!(direct.name.as_deref(), Some("chr1_sample"));
+    assert!(direct
+        .attributes
+        .contains(&("cycle".to_string(), "2".to_string())));
+    assert!(!direct
+        .attributes
+        .iter()
+        .any(|(key, _)| key == "orientation"));
+
+    let inverse = task.to_annotation("chr1 sample-reversed", 3, &matrix, 1000);
+    assert_eq!(inverse.seqid, "chr1");
+    assert_eq!((inverse.start, inverse.end), (840, 900));
+    assert_eq!(inverse.strand, None);
+    assert_eq!(inverse.attributes[0].1, "chr1_sample-reversed.3");
+    assert!(inverse
+        .attributes
+        .contains(&("orientation".to_string(), "reversed".to_string())));
+}
+
+fn get_opts() -> CMDOptions {
+    let args = Args::parse_from([
+        "latent-repeat-search",
+        "--repeat-length",
+        "4",
+        "--query-offset",
+        "2",
+        "--repeats",
+        "1",
+    ]);
+
+    CMDOptions::from_args(&args).unwrap()
+}
+
+#[test]
+fn starting_values_errors_test() {
+    let opts = get_opts();
+    let mut rng = get_rng(0);
+    let matrix = Array2::<f64>::from_elem((4, 4), 1f64);
+
+    // A single window gives a single score
+    let query = DNA::str_to_vec("AC").unwrap();
+    let result = calculate_starting_values(&query, &Scorer::Matrix(matrix), &opts, &mut rng);
+    assert!(result.unwrap_err().msg.contains("1 window scores"));
+
+    // Every window scores 0
+    let query = DNA::str_to_vec("ACGTACGTACGTACGTACGT").unwrap();
+    let zeros = Array2::<f64>::zeros((4, 4));
+    let result = calculate_starting_values(&query, &Scorer::Matrix(zeros), &opts, &mut rng);
+    assert!(result.is_err());
+}
+
+#[test]
+fn short_sequence_is_skipped_test() {
+    let opts = get_opts();
+    let mut rng = get_rng(0);
+
+    let result = perform_calculation_per_sequence(&opts, b"AC", "short", 0, &mut rng).unwrap();
+    assert!(result.is_empty());
+}
+
+fn get_task(left_coord: usize, right_coord: usize) -> Task {
+    Task {
+        alignment: PWMAlignment::empty(),
+        z: 0f64,
+        p_value: 0f64,
+        q_value: 0f64,
+        left_coord,
+        right_coord,
+        cycle: 1,
+    }
+}
+
+fn get_copy(left: usize, right: usize, reversed: bool, number: usize) -> RepeatCopy {
+    RepeatCopy {
+        left,
+        right,
+        reversed,
+        family: "chr1".to_string(),
+        number,
+    }
+}
+
+fn get_record() -> SequenceRecord {
+    SequenceRecord {
+        id: "chr1".to_string(),
+        description: None,
+        sequence: b"ACGTACGTAC".to_vec(),
+        quality: None,
+        features: vec![],
+    }
+}
+
+#[test]
+fn get_copies_test() {
+    let matrix = Array2::<f64>::zeros((4, 1));
+    let result = HashMap::from([
+        ("chr1".to_string(), (vec![get_task(2, 5)], matrix.clone())),
+        ("chr1-reversed".to_string(), (vec![get_task(1, 4)], matrix)),
+    ]);
+    let lengths = HashMap::from([("chr1".to_string(), 10), ("chr1-reversed".to_string(), 10)]);
+
+    let copies = get_copies(&result, &lengths);
+    let mut copies: Vec<(usize, usize, bool)> = copies["chr1"]
+        .iter()
+        .map(|copy| (copy.left, copy.right, copy.reversed))
+        .collect();
+    copies.sort();
+
+    // Inverse coordinates are flipped to the forward strand
+    assert_eq!(copies, vec![(2, 5, false), (6, 9, true)]);
+}
+
+#[test]
+fn copy_records_test() {
+    let copies = vec![
+        get_copy(2, 5, false, 1),
+        get_copy(6, 9, true, 2),
+        get_copy(8, 12, false, 3),
+    ];
+
+    // The last copy is out of the sequence
+    let records = get_copy_records(&get_record(), 0, &copies);
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].head(), "chr1.1 chr1:3-5(+)");
+    assert_eq!(records[0].sequence, b"GTA");
+    assert_eq!(records[1].head(), "chr1.2 chr1:7-9 orientation=reversed");
+    assert_eq!(records[1].sequence, b"GTA");
+
+    // Copies of a region are in genome coordinates
+    let copies = vec![get_copy(102, 105, false, 1)];
+    let records = get_copy_records(&get_record(), 100, &copies);
+    assert_eq!(records[0].head(), "chr1.1 chr1:103-105(+)");
+    assert_eq!(records[0].sequence, b"GTA");
+}
+
+#[test]
+fn mask_copies_test() {
+    let copies = vec![get_copy(2, 5, false, 1), get_copy(8, 12, true, 2)];
+
+    let mut record = get_record();
+    mask_copies(&mut record, 0, &copies, false);
+    // The second copy runs past the record and is masked up to its end
+    assert_eq!(record.sequence, b"ACNNNCGTNN");
+
+    let mut record = get_record();
+    mask_copies(&mut record, 0, &copies, true);
+    assert_eq!(record.sequence, b"ACgtaCGTac");
+
+    let mut record = get_record();
+    mask_copies(&mut record, 100, &[get_copy(107, 110, false, 1)], false);
+    assert_eq!(record.sequence, b"ACGTACGNNN");
+}
@@ -1,12 +1,20 @@
 use crate::args::Args;
-use crate::cmd::{run_root_cmd, CMDOptions};
+use crate::cmd::{run_root_cmd, CMDOptions, CMDOutput};
+use crate::engine::export::{export_sequences, get_copies};
 use crate::engine::filter;
 use crate::error::Error;
+use aligner_core::annotation::{save_annotations, AnnotationFormat};
+use aligner_core::enums::DNA;
+use aligner_core::motif::{save_motifs, Motif, MotifFormat};
+use aligner_core::pwm::analysis::PWMProfile;
+use aligner_core::serialization::save_json;
+use aligner_core::AlignmentTrait;
 use aligner_helpers::csv::{CsvInput, Record};
 use clap::Parser;
 use ndarray::Array2;
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 
 extern crate pretty_env_logger;
 #[macro_use]
@@ -17,57 +25,225 @@ mod cmd;
 mod engine;
 mod error;
 
+const LOGO_PSEUDOCOUNT: f64 = 1f64;
+
 fn main() -> Result<(), Error> {
     pretty_env_logger::init();
 
     let args = Args::parse();
 
+    let output_format = match args.output_format.as_str() {
+        "csv" => None,
+        format => match AnnotationFormat::from_str(format) {
+            Ok(format) => Some(format),
+            Err(_) => {
+                return Err(Error {
+                    msg: format!("unknown output format {}", format),
+                })
+            }
+        },
+    };
+
     let output_path = match args.output.clone() {
         Some(output) => PathBuf::from(output),
-        None => std::env::current_dir().unwrap().join("output.csv"),
+        None => std::env::current_dir()
+            .unwrap()
+            .join(format!("output.{}", args.output_format.to_lowercase())),
+    };
+
+    let matrices_format = match args.matrices_format.as_str() {
+        "json" => None,
+        format => match MotifFormat::from_str(format) {
+            Ok(format) => Some(format),
+            Err(_) => {
+                return Err(Error {
+                    msg: format!("unknown matrices format {}", format),
+                })
+            }
+        },
     };
 
     let matrices_output_path = match args.output.clone() {
-        Some(output) => PathBuf::from(&format!("{}.matrices.json", output)),
-        None => std::env::current_dir().unwrap().join("matrices.json"),
+        Some(output) => PathBuf::from(&format!(
+            "{}.matrices.{}",
+            output,
+            args.matrices_format.to_lowercase()
+        )),
+        None => std::env::current_dir()
+            .unwrap()
+            .join(format!("matrices.{}", args.matrices_format.to_lowercase())),
     };
 
-    let opts = CMDOptions::from_args(&args);
+    let opts = CMDOptions::from_args(&args)?;
 
-    let result = match run_root_cmd(&opts) {
-        Ok(result) => result,
-        Err(err) => return Err(err),
-    };
+    let masked_path = args.masked_fasta.as_ref().map(PathBuf::from);
+    let copies_path = args.copies_fasta.as_ref().map(PathBuf::from);
 
-    let mut wtr = CsvInput::new(output_path.as_path()).unwrap();
+    if opts.testing && (masked_path.is_some() || copies_path.is_some()) {
+        return Err(Error {
+            msg: "FASTA exports need an --input".to_string(),
+        });
+    }
+
+    let CMDOutput { result, lengths } = run_root_cmd(&opts)?;
+
+    let mut wtr = match output_format {
+        Some(_) => None,
+        None => Some(CsvInput::new(output_path.as_path()).unwrap()),
+    };
+    let mut annotations = vec![];
 
     let mut matrices = HashMap::<String, Array2<f64>>::new();
+    let mut motifs = vec![];
 
     for (key, value) in result.iter() {
-        for task in value.0.iter() {
-            wtr.write(&Record {
-                name: key.to_owned(),
-                z_value: task.z,
-                left_coord: task.left_coord,
-                right_coord: task.right_coord,
-            })
-            .unwrap();
+        for (i, task) in value.0.iter().enumerate() {
+            match wtr.as_mut() {
+                Some(wtr) => wtr
+                    .write(&Record {
+                        name: key.to_owned(),
+                        z_value: task.z,
+                        p_value: Some(task.p_value),
+                        q_value: Some(task.q_value),
+                        left_coord: task.left_coord,
+                        right_coord: task.right_coord,
+                    })
+                    .unwrap(),
+                None => annotations.push(task.to_annotation(key, i + 1, &value.1, lengths[key])),
+            }
         }
 
         matrices.insert(key.clone(), value.1.clone());
+
+        if let Some(first) = value.0.first() {
+            let mut counts = first.alignment.get_frequency_matrix();
+            for task in value.0[1..].iter() {
+                counts = counts + task.alignment.get_frequency_matrix();
+            }
+
+            motifs.push(Motif::new(key, counts).unwrap());
+        }
+    }
+
+    // Results come from a map, so ties are broken by the end and the name
+    // to keep the output stable between runs
+    if let Some(format) = output_format {
+        annotations.sort_by(|a, b| {
+            (&a.seqid, a.start, a.end, &a.name).cmp(&(&b.seqid, b.start, b.end, &b.name))
+        });
+        save_annotations(output_path.as_path(), &annotations, format).unwrap();
     }
 
+    let run_output_path = PathBuf::from(format!("{}.run.json", output_path.display()));
+
     std::fs::write(
-        matrices_output_path.as_path(),
-        serde_json::to_string(&matrices).unwrap(),
+        run_output_path.as_path(),
+        serde_json::json!({
+            "random_seed": opts.random_seed,
+            "threads": opts.threads,
+            "null_model": format!("{:?}", opts.null_model),
+            "significance": opts.significance,
+        })
+        .to_string(),
     )
     .unwrap();
 
+    let results_output_path = PathBuf::from(format!("{}.results.json", output_path.display()));
+
+    save_json(results_output_path.as_path(), &result).unwrap();
+
+    if masked_path.is_some() || copies_path.is_some() {
+        export_sequences(
+            &opts,
+            &get_copies(&result, &lengths),
+            masked_path.as_deref(),
+            args.soft_mask,
+            copies_path.as_deref(),
+        )?;
+    }
+
+    let mut logo_paths = vec![];
+
+    if args.logo {
+        for motif in motifs.iter() {
+            match write_logo(motif, &output_path) {
+                Ok(logo_path) => logo_paths.push(logo_path),
+                Err(err) => warn!("Skipping the logo of {}: {}", motif.name, err.msg),
+            }
+        }
+    }
+
+    match matrices_format {
+        Some(format) => save_motifs(matrices_output_path.as_path(), &motifs, format).unwrap(),
+        None => std::fs::write(
+            matrices_output_path.as_path(),
+            serde_json::to_string(&matrices).unwrap(),
+        )
+        .unwrap(),
+    }
+
     println!(
-        "\nOutput written to:\n 1. Result: {}\n 2. Matrices: {}",
-        output_path.into_os_string().into_string().unwrap(),
-        matrices_output_path.into_os_string().into_string().unwrap()
+        "\nOutput written to:\n 1. Result: {}\n 2. Matrices: {}\n 3. Run parameters: {}\n 4. Alignments: {}",
+        output_path.display(),
+        matrices_output_path.display(),
+        run_output_path.display(),
+        results_output_path.display()
     );
 
+    let mut extra_paths: Vec<(&str, &PathBuf)> =
+        logo_paths.iter().map(|path| ("Logo", path)).collect();
+
+    if let Some(path) = masked_path.as_ref() {
+        extra_paths.push(("Masked FASTA", path));
+    }
+
+    if let Some(path) = copies_path.as_ref() {
+        extra_paths.push(("Copies FASTA", path));
+    }
+
+    for (i, (title, path)) in extra_paths.iter().enumerate() {
+        println!(" {}. {}: {}", i + 5, title, path.display());
+    }
+
+    println!("\nRandom seed: {}", opts.random_seed);
+
     Ok(())
 }
+
+// Writes the logo of a motif next to the result and logs its consensus
+fn write_logo(motif: &Motif, output_path: &Path) -> Result<PathBuf, Error> {
+    let profile = PWMProfile::<DNA>::from_counts(
+        &motif.counts,
+        &PWMProfile::<DNA>::uniform_background(),
+        LOGO_PSEUDOCOUNT,
+    )
+    .map_err(|err| Error {
+        msg: format!("unable to build a profile: {:?}", err),
+    })?;
+
+    let svg = profile.logo_svg().map_err(|err| Error {
+        msg: format!("unable to draw a logo: {:?}", err),
+    })?;
+
+    let logo_path = PathBuf::from(format!(
+        "{}.{}.logo.svg",
+        output_path.display(),
+        motif
+            .name
+            .split_whitespace()
+            .collect::<Vec<&str>>()
+            .join("_")
+    ));
+
+    if let Err(err) = std::fs::write(logo_path.as_path(), svg) {
+        return Err(Error {
+            msg: format!("unable to write {}: {}", logo_path.display(), err),
+        });
+    }
+
+    if let Ok(consensus) = profile.consensus_string() {
+        info!("Consensus of {}: {}", motif.name, consensus);
+    }
+
+    Ok(logo_path)
+}
@@ -1,12 +1,13 @@
 use crate::{Error, Result};
 use ndarray::Array1;
-use rand::{thread_rng, Rng};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
 use std::cmp::PartialEq;
 use std::fmt::Debug;
 use std::hash::Hash;
 use std::str;
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum Direction {
     Top,
     Left,
@@ -52,6 +53,12 @@ impl PartialEq for Direction {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Strand {
+    Forward,
+    Reverse,
+}
+
 #[derive(Debug, Clone, Copy, Eq)]
 pub enum Protein {
     A = 0,
@@ -191,8 +198,11 @@ where
     fn from_u8_vec_with_freqs_and_indices(
         vec: &[u8],
     ) -> Result<(Vec<Self>, Array1<f64>, Vec<Index>)>;
-    fn random_seq(length: usize) -> Result<Vec<Self>>;
-    fn random_seq_with_freqs(length: usize) -> Result<(Vec<Self>, Array1<f64>)>;
+    fn random_seq<R: Rng + ?Sized>(length: usize, rng: &mut R) -> Result<Vec<Self>>;
+    fn random_seq_with_freqs<R: Rng + ?Sized>(
+        length: usize,
+        rng: &mut R,
+    ) -> Result<(Vec<Self>, Array1<f64>)>;
     fn blank() -> Self;
     fn pos() -> Self;
     fn volume() -> usize;
@@ -362,24 +372,24 @@ impl BioData for Protein {
         Ok((result, freqs, indices))
     }
 
-    fn random_seq(length: usize) -> Result<Vec<Protein>> {
-        let mut thread_rng = thread_rng();
-
+    fn random_seq<R: Rng + ?Sized>(length: usize, rng: &mut R) -> Result<Vec<Protein>> {
         let mut result = vec![];
         for _ in 0..length {
-            result.push(thread_rng.gen_range(0..Protein::volume()).into())
+            result.push(rng.gen_range(0..Protein::volume()).into())
         }
 
         Ok(result)
     }
 
-    fn random_seq_with_freqs(length: usize) -> Result<(Vec<Protein>, Array1<f64>)> {
-        let mut thread_rng = thread_rng();
+    fn random_seq_with_freqs<R: Rng + ?Sized>(
+        length: usize,
+        rng: &mut R,
+    ) -> Result<(Vec<Protein>, Array1<f64>)> {
         let mut freqs = Array1::<f64>::zeros(Self::volume());
 
         let mut result = vec![];
         for _ in 0..length {
-            let elem = thread_rng.gen_range(0..Protein::volume());
+            let elem = rng.gen_range(0..Protein::volume());
             result.push(elem.into());
             freqs[elem] += 1f64;
         }
@@ -526,24 +536,24 @@ impl BioData for DNA {
         Ok((result, freqs, indices))
     }
 
-    fn random_seq(length: usize) -> Result<Vec<DNA>> {
-        let mut thread_rng = thread_rng();
-
+    fn random_seq<R: Rng + ?Sized>(length: usize, rng: &mut R) -> Result<Vec<DNA>> {
         let mut result = vec![];
         for _ in 0..length {
-            result.push(thread_rng.gen_range(0..DNA::volume()).into())
+            result.push(rng.gen_range(0..DNA::volume()).into())
         }
 
         Ok(result)
     }
 
-    fn random_seq_with_freqs(length: usize) -> Result<(Vec<DNA>, Array1<f64>)> {
-        let mut thread_rng = thread_rng();
+    fn random_seq_with_freqs<R: Rng + ?Sized>(
+        length: usize,
+        rng: &mut R,
+    ) -> Result<(Vec<DNA>, Array1<f64>)> {
         let mut freqs = Array1::<f64>::zeros(Self::volume());
 
         let mut result = vec![];
         for _ in 0..length {
-            let elem = thread_rng.gen_range(0..DNA::volume());
+            let elem = rng.gen_range(0..DNA::volume());
             result.push(elem.into());
             freqs[elem] += 1f64;
         }
@@ -107,11 +107,19 @@ impl<T: BioData + Into<usize> + Copy + Eq> AlignerTrait<T, PWMAlignment<T>>
         matrix: &Array2<f64>,
         heuristics: Option<Heuristics>,
     ) -> Result<AlignmentResult<T, PWMAlignment<T>>> {
-        let params = match heuristics {
+        let mut params = match heuristics {
             Some(h) => h,
             None => return Err(Error::MissingArgument),
         };
 
+        if matrix.len_of(Axis(0)) != T::volume() || params.frequencies.len() != T::volume() {
+            return Err(Error::MatrixShapeError);
+        }
+
+        if (params.r_squared - 0 as f64).abs() < f64::EPSILON {
+            params.r_squared = (matrix.len_of(Axis(0)) * matrix.len_of(Axis(1))) as f64;
+        }
+
         let mut transformed_matrix =
             transform_matrix(matrix, params.kd, params.r_squared, &params.frequencies).unwrap();
 
@@ -0,0 +1,340 @@
+use crate::alignment::PWMAlignment;
+use crate::{BioData, Error, Result};
+use ndarray::{Array1, Array2, Axis};
+use std::marker::PhantomData;
+
+const PSEUDOCOUNT: f64 = 1f64;
+
+const MM: usize = 0;
+const MI: usize = 1;
+const MD: usize = 2;
+const IM: usize = 3;
+const II: usize = 4;
+const DM: usize = 5;
+const DD: usize = 6;
+
+const TRANSITIONS: usize = 7;
+// Transitions which end in a state that emits a residue
+const EMITTING: [usize; 5] = [MM, MI, IM, II, DM];
+
+#[cfg(test)]
+mod test;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum State {
+    Begin,
+    Match,
+    Insert,
+    Delete,
+}
+
+#[derive(Debug, Clone)]
+pub struct ProfileHMM<T: BioData + Into<usize> + Copy + Eq> {
+    pub length: usize,
+    pub match_emissions: Array2<f64>,
+    pub transitions: Array2<f64>,
+    pub background: Array1<f64>,
+    phantom: PhantomData<T>,
+}
+
+impl<T: BioData + Into<usize> + Copy + Eq> ProfileHMM<T> {
+    pub fn from_alignments(
+        alignments: &[PWMAlignment<T>],
+        background: &Array1<f64>,
+    ) -> Result<ProfileHMM<T>> {
+        let length = match alignments.first() {
+            Some(alignment) => alignment.dim,
+            None => return Err(Error::ResultIsEmpty),
+        };
+
+        if length == 0 || alignments.iter().any(|alignment| alignment.dim != length) {
+            return Err(Error::MatrixShapeError);
+        }
+
+        if background.len() != T::volume() || background.iter().any(|b| *b <= 0f64) {
+            return Err(Error::ValidationError);
+        }
+
+        let background = background / background.sum();
+
+        let mut emission_counts = Array2::<f64>::zeros((T::volume(), length));
+        let mut transition_counts = Array2::<f64>::zeros((length + 1, TRANSITIONS));
+
+        for alignment in alignments.iter() {
+            let mut previous: Option<(State, usize)> = None;
+
+            for (column, elem) in alignment.numbered.iter().zip(alignment.query.iter()) {
+                let current = if *column == 0 {
+                    (State::Insert, previous.map(|(_, k)| k).unwrap_or(0))
+                } else if *elem == T::blank() {
+                    (State::Delete, *column)
+                } else {
+                    emission_counts[[(*elem).into(), *column - 1]] += 1f64;
+                    (State::Match, *column)
+                };
+
+                if let Some((state, k)) = previous {
+                    let transition = match (state, current.0) {
+                        (State::Match, State::Match) => Some(MM),
+                        (State::Match, State::Insert) => Some(MI),
+                        (State::Match, State::Delete) => Some(MD),
+                        (State::Insert, State::Match) => Some(IM),
+                        (State::Insert, State::Insert) => Some(II),
+                        (State::Delete, State::Match) => Some(DM),
+                        (State::Delete, State::Delete) => Some(DD),
+                        _ => None,
+                    };
+
+                    if let Some(transition) = transition {
+                        transition_counts[[k, transition]] += 1f64;
+                    }
+                }
+
+                previous = Some(current);
+            }
+        }
+
+        let mut match_emissions = Array2::<f64>::zeros((T::volume(), length));
+        for (k, column) in emission_counts.axis_iter(Axis(1)).enumerate() {
+            let total = column.sum() + PSEUDOCOUNT;
+
+            for (a, count) in column.iter().enumerate() {
+                let p = (count + PSEUDOCOUNT * background[a]) / total;
+                match_emissions[[a, k]] = (p / background[a]).ln();
+            }
+        }
+
+        // Log-odds against a null model which emits background residues and
+        // stays in its state with probability length / (length + 1), so every
+        // emitting transition is paid for by one null transition
+        let null_transition = (length as f64 / (length as f64 + 1f64)).ln();
+
+        let mut transitions = Array2::<f64>::zeros((length + 1, TRANSITIONS));
+        for k in 0..=length {
+            for group in [&[MM, MI, MD][..], &[IM, II][..], &[DM, DD][..]] {
+                let total = group
+                    .iter()
+                    .map(|t| transition_counts[[k, *t]] + PSEUDOCOUNT)
+                    .sum::<f64>();
+
+                for t in group.iter() {
+                    transitions[[k, *t]] =
+                        ((transition_counts[[k, *t]] + PSEUDOCOUNT) / total).ln();
+
+                    if EMITTING.contains(t) {
+                        transitions[[k, *t]] -= null_transition;
+                    }
+                }
+            }
+        }
+
+        Ok(ProfileHMM {
+            length,
+            match_emissions,
+            transitions,
+            background,
+            phantom: PhantomData,
+        })
+    }
+
+    pub fn viterbi(&self, query: &[T]) -> Result<f64> {
+        Ok(self.align(query)?.f)
+    }
+
+    pub fn align(&self, query: &[T]) -> Result<PWMAlignment<T>> {
+        let dim = (query.len() + 1, self.length + 1);
+        let entry = -(self.length as f64).ln();
+
+        let mut vm = Array2::<f64>::from_elem(dim, f64::NEG_INFINITY);
+        let mut vi = Array2::<f64>::from_elem(dim, f64::NEG_INFINITY);
+        let mut vd = Array2::<f64>::from_elem(dim, f64::NEG_INFINITY);
+
+        let mut tm = Array2::<State>::from_elem(dim, State::Begin);
+        let mut ti = Array2::<State>::from_elem(dim, State::Begin);
+        let mut td = Array2::<State>::from_elem(dim, State::Begin);
+
+        let mut best = (f64::NEG_INFINITY, 0, 0);
+
+        for (y, elem) in query.iter().enumerate() {
+            let i = y + 1;
+            let a = self.emission_index(elem)?;
+
+            for k in 1..=self.length {
+                let (score, state) = max_state(&[
+                    (entry, State::Begin),
+                    (
+                        vm[[i - 1, k - 1]] + self.transitions[[k - 1, MM]],
+                        State::Match,
+                    ),
+                    (
+                        vi[[i - 1, k - 1]] + self.transitions[[k - 1, IM]],
+                        State::Insert,
+                    ),
+                    (
+                        vd[[i - 1, k - 1]] + self.transitions[[k - 1, DM]],
+                        State::Delete,
+                    ),
+                ]);
+                vm[[i, k]] = score + self.match_emissions[[a, k - 1]];
+                tm[[i, k]] = state;
+
+                if vm[[i, k]] > best.0 {
+                    best = (vm[[i, k]], i, k);
+                }
+
+                if k < self.length {
+                    let (score, state) = max_state(&[
+                        (vm[[i - 1, k]] + self.transitions[[k, MI]], State::Match),
+                        (vi[[i - 1, k]] + self.transitions[[k, II]], State::Insert),
+                    ]);
+                    vi[[i, k]] = score;
+                    ti[[i, k]] = state;
+                }
+
+                if k > 1 {
+                    let (score, state) = max_state(&[
+                        (vm[[i, k - 1]] + self.transitions[[k - 1, MD]], State::Match),
+                        (
+                            vd[[i, k - 1]] + self.transitions[[k - 1, DD]],
+                            State::Delete,
+                        ),
+                    ]);
+                    vd[[i, k]] = score;
+                    td[[i, k]] = state;
+                }
+            }
+        }
+
+        let (f, end_i, end_k) = best;
+
+        if !f.is_finite() {
+            return Err(Error::ResultIsEmpty);
+        }
+
+        let (mut numbered_alignment, mut query_alignment) = (vec![], vec![]);
+        let (mut i, mut k) = (end_i, end_k);
+        let mut state = State::Match;
+
+        loop {
+            let previous = match state {
+                State::Match => {
+                    numbered_alignment.push(k);
+                    query_alignment.push(query[i - 1]);
+                    tm[[i, k]]
+                }
+                State::Insert => {
+                    numbered_alignment.push(0);
+                    query_alignment.push(query[i - 1]);
+                    ti[[i, k]]
+                }
+                State::Delete => {
+                    numbered_alignment.push(k);
+                    query_alignment.push(T::blank());
+                    td[[i, k]]
+                }
+                State::Begin => break,
+            };
+
+            match state {
+                State::Match => {
+                    if previous != State::Begin {
+                        i -= 1;
+                        k -= 1;
+                    }
+                }
+                State::Insert => i -= 1,
+                State::Delete => k -= 1,
+                State::Begin => {}
+            }
+
+            state = previous;
+        }
+
+        numbered_alignment.reverse();
+        query_alignment.reverse();
+
+        Ok(PWMAlignment {
+            numbered: numbered_alignment,
+            query: query_alignment,
+            dim: self.length,
+            coords: ((k, end_k + 1), (i, end_i + 1)),
+            f,
+        })
+    }
+
+    pub fn forward(&self, query: &[T]) -> Result<f64> {
+        let dim = (query.len() + 1, self.length + 1);
+        let entry = -(self.length as f64).ln();
+
+        let mut fm = Array2::<f64>::from_elem(dim, f64::NEG_INFINITY);
+        let mut fi = Array2::<f64>::from_elem(dim, f64::NEG_INFINITY);
+        let mut fd = Array2::<f64>::from_elem(dim, f64::NEG_INFINITY);
+
+        let mut total = f64::NEG_INFINITY;
+
+        for (y, elem) in query.iter().enumerate() {
+            let i = y + 1;
+            let a = self.emission_index(elem)?;
+
+            for k in 1..=self.length {
+                fm[[i, k]] = self.match_emissions[[a, k - 1]]
+                    + log_sum_exp(&[
+                        entry,
+                        fm[[i - 1, k - 1]] + self.transitions[[k - 1, MM]],
+                        fi[[i - 1, k - 1]] + self.transitions[[k - 1, IM]],
+                        fd[[i - 1, k - 1]] + self.transitions[[k - 1, DM]],
+                    ]);
+
+                total = log_sum_exp(&[total, fm[[i, k]]]);
+
+                if k < self.length {
+                    fi[[i, k]] = log_sum_exp(&[
+                        fm[[i - 1, k]] + self.transitions[[k, MI]],
+                        fi[[i - 1, k]] + self.transitions[[k, II]],
+                    ]);
+                }
+
+                if k > 1 {
+                    fd[[i, k]] = log_sum_exp(&[
+                        fm[[i, k - 1]] + self.transitions[[k - 1, MD]],
+                        fd[[i, k - 1]] + self.transitions[[k - 1, DD]],
+                    ]);
+                }
+            }
+        }
+
+        Ok(total)
+    }
+
+    fn emission_index(&self, elem: &T) -> Result<usize> {
+        let a: usize = (*elem).into();
+
+        if a >= T::volume() {
+            return Err(Error::CharIsNotMatchable);
+        }
+
+        Ok(a)
+    }
+}
+
+fn max_state(candidates: &[(f64, State)]) -> (f64, State) {
+    let mut result = (f64::NEG_INFINITY, State::Begin);
+
+    for candidate in candidates.iter() {
+        if candidate.0 > result.0 {
+            result = *candidate;
+        }
+    }
+
+    result
+}
+
+fn log_sum_exp(values: &[f64]) -> f64 {
+    let max = values.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+
+    if !max.is_finite() {
+        return max;
+    }
+
+    max + values.iter().map(|v| (v - max).exp()).sum::<f64>().ln()
+}
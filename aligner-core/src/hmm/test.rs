@@ -0,0 +1,123 @@
+use crate::alignment::PWMAlignment;
+use crate::enums::{BioData, DNA};
+use crate::hmm::{ProfileHMM, DM, II, IM, MD, MI, MM};
+use crate::Error;
+use ndarray::Array1;
+
+fn alignment(numbered: &[usize], query: &str) -> PWMAlignment<DNA> {
+    PWMAlignment {
+        numbered: numbered.to_vec(),
+        query: DNA::str_to_vec(query).unwrap(),
+        dim: 3,
+        coords: ((1, 4), (1, query.len() + 1)),
+        f: 0f64,
+    }
+}
+
+fn get_hmm() -> ProfileHMM<DNA> {
+    let alignments = vec![
+        alignment(&[1, 2, 3], "ACG"),
+        alignment(&[1, 2, 3], "ACG"),
+        alignment(&[1, 0, 2, 3], "ATCG"),
+        alignment(&[1, 2, 3], "A_G"),
+    ];
+
+    ProfileHMM::from_alignments(&alignments, &Array1::from_elem(4, 0.25)).unwrap()
+}
+
+fn assert_close(value: f64, expected: f64) {
+    assert!(
+        (value - expected).abs() < 1e-12,
+        "{} != {}",
+        value,
+        expected
+    );
+}
+
+#[test]
+fn from_alignments_test() {
+    let hmm = get_hmm();
+    let (a, c, g) = (DNA::A as usize, DNA::C as usize, DNA::G as usize);
+
+    // Pseudocounts are spread over the background
+    assert_close(hmm.match_emissions[[a, 0]], (0.85f64 / 0.25).ln());
+    assert_close(hmm.match_emissions[[c, 1]], (0.8125f64 / 0.25).ln());
+    assert_close(hmm.match_emissions[[a, 1]], (0.0625f64 / 0.25).ln());
+    assert_close(hmm.match_emissions[[g, 2]], (0.85f64 / 0.25).ln());
+
+    // Emitting transitions are scored against the null self-loop of 3/4
+    let null = 0.75f64.ln();
+    assert_close(hmm.transitions[[1, MM]], (3f64 / 7f64).ln() - null);
+    assert_close(hmm.transitions[[1, MI]], (2f64 / 7f64).ln() - null);
+    assert_close(hmm.transitions[[1, MD]], (2f64 / 7f64).ln());
+    assert_close(hmm.transitions[[1, IM]], (2f64 / 3f64).ln() - null);
+    assert_close(hmm.transitions[[1, II]], (1f64 / 3f64).ln() - null);
+    assert_close(hmm.transitions[[2, DM]], (2f64 / 3f64).ln() - null);
+    assert_close(hmm.transitions[[2, MM]], (4f64 / 6f64).ln() - null);
+}
+
+#[test]
+fn viterbi_test() {
+    let hmm = get_hmm();
+    let (a, c, g) = (DNA::A as usize, DNA::C as usize, DNA::G as usize);
+
+    let query = DNA::str_to_vec("TTACGTT").unwrap();
+    let alignment = hmm.align(&query).unwrap();
+
+    assert_eq!(alignment.numbered, vec![1, 2, 3]);
+    assert_eq!(alignment.query, DNA::str_to_vec("ACG").unwrap());
+    assert_eq!(alignment.coords, ((1, 4), (3, 6)));
+
+    let expected = -3f64.ln()
+        + hmm.match_emissions[[a, 0]]
+        + hmm.transitions[[1, MM]]
+        + hmm.match_emissions[[c, 1]]
+        + hmm.transitions[[2, MM]]
+        + hmm.match_emissions[[g, 2]];
+    assert_close(alignment.f, expected);
+    assert_close(hmm.viterbi(&query).unwrap(), expected);
+}
+
+#[test]
+fn forward_test() {
+    let hmm = get_hmm();
+
+    for query in ["ACG", "ATCG", "AG", "TTTT", "GACGTACG"] {
+        let query = DNA::str_to_vec(query).unwrap();
+        assert!(hmm.forward(&query).unwrap() >= hmm.viterbi(&query).unwrap());
+    }
+}
+
+#[test]
+fn hmm_errors_test() {
+    let background = Array1::from_elem(4, 0.25);
+
+    assert!(matches!(
+        ProfileHMM::<DNA>::from_alignments(&[], &background),
+        Err(Error::ResultIsEmpty)
+    ));
+
+    let mut shorter = alignment(&[1, 2], "AC");
+    shorter.dim = 2;
+    assert!(matches!(
+        ProfileHMM::from_alignments(&[alignment(&[1, 2, 3], "ACG"), shorter], &background),
+        Err(Error::MatrixShapeError)
+    ));
+
+    for background in [
+        Array1::from(vec![0.5, 0.5, 0f64, 0f64]),
+        Array1::from_elem(3, 0.25),
+    ] {
+        assert!(matches!(
+            ProfileHMM::from_alignments(&[alignment(&[1, 2, 3], "ACG")], &background),
+            Err(Error::ValidationError)
+        ));
+    }
+
+    let hmm = get_hmm();
+    assert!(matches!(hmm.align(&[]), Err(Error::ResultIsEmpty)));
+    assert!(matches!(
+        hmm.align(&[DNA::A, DNA::Blank]),
+        Err(Error::CharIsNotMatchable)
+    ));
+}
@@ -5,19 +5,32 @@ extern crate ndarray;
 
 pub mod alignment;
 pub mod alignment_result;
+pub mod annotation;
 pub mod enums;
 pub mod heuristic;
+pub mod hmm;
+pub mod masking;
+pub mod matrices;
+pub mod motif;
 pub mod pwm;
+pub mod reader;
+pub mod sam;
+pub mod serialization;
 pub mod simple;
 pub mod statistics;
+pub mod visualisation;
 
+use crate::alignment::stats::AlignmentStats;
 use crate::alignment_result::AlignmentResult;
-use crate::enums::{BioData, Protein};
+use crate::enums::{BioData, DNA};
 use aligner_helpers::files::convert_csv_to_matrix;
-use ndarray::{Array1, Array2};
-use rand::{thread_rng, Rng};
+use ndarray::{arr1, Array1, Array2};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
 use std::result;
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Heuristics {
     pub kd: f64,
     pub r_squared: f64,
@@ -42,6 +55,12 @@ where
 pub trait AlignmentTrait<T: BioData + Into<usize> + Eq + Copy> {
     fn get_frequency_matrix(&self) -> Array2<f64>;
     fn get_alignment(&self, matrix: &Array2<f64>) -> Vec<T>;
+    fn get_stats(
+        &self,
+        matrix: &Array2<f64>,
+        query_length: usize,
+        target_length: usize,
+    ) -> AlignmentStats;
 }
 
 #[derive(Debug)]
@@ -54,6 +73,8 @@ pub enum Error {
     CalculationError,
     ValidationError,
     MatrixShapeError,
+    ParseError,
+    IOError,
 }
 
 pub type Result<T> = result::Result<T, Error>;
@@ -89,8 +110,33 @@ pub fn get_blosum62() -> Array2<f64> {
     )
 }
 
-pub fn get_random_pwm(length: usize) -> Array2<f64> {
-    let mut thread_rng = thread_rng();
+pub fn get_robinson_frequencies() -> Array1<f64> {
+    arr1(&[
+        0.07805, 0.05129, 0.04487, 0.05364, 0.01925, 0.04264, 0.06295, 0.07377, 0.02199, 0.05142,
+        0.09019, 0.05744, 0.02243, 0.03856, 0.05203, 0.07120, 0.05841, 0.01330, 0.03216, 0.06441,
+        0f64, 0f64, 0f64, 0f64,
+    ])
+}
+
+// Match/mismatch scoring for nucleotides, indexed like the other matrices
+pub fn get_dna_matrix(matching: f64, mismatching: f64) -> Array2<f64> {
+    Array2::from_shape_fn((DNA::volume(), DNA::volume()), |(y, x)| {
+        if y == x {
+            matching
+        } else {
+            mismatching
+        }
+    })
+}
+
+pub fn get_random_pwm<T: BioData, R: Rng + ?Sized>(length: usize, rng: &mut R) -> Array2<f64> {
+    Array2::from_shape_simple_fn((T::volume(), length), || rng.gen_range(-1..2) as f64)
+}
+
+pub fn get_rng(seed: u64) -> StdRng {
+    StdRng::seed_from_u64(seed)
+}
 
-    Array2::from_shape_simple_fn((4, length), || thread_rng.gen_range(-1..2) as f64)
+pub fn get_thread_seeds<R: Rng + ?Sized>(rng: &mut R, threads: usize) -> Vec<u64> {
+    (0..threads).map(|_| rng.gen()).collect()
 }
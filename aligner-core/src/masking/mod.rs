@@ -0,0 +1,215 @@
+use crate::enums::{BioData, DNA};
+
+#[cfg(test)]
+mod test;
+
+const SEG_WINDOW: usize = 12;
+const SEG_LOCUT: f64 = 2.2;
+const SEG_HICUT: f64 = 2.5;
+const DUST_WINDOW: usize = 64;
+const DUST_LEVEL: f64 = 20f64;
+const TRIPLETS: usize = 64;
+
+#[derive(Debug, Clone)]
+pub struct SegOptions {
+    pub window: usize,
+    pub locut: f64,
+    pub hicut: f64,
+}
+
+impl Default for SegOptions {
+    fn default() -> Self {
+        SegOptions {
+            window: SEG_WINDOW,
+            locut: SEG_LOCUT,
+            hicut: SEG_HICUT,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DustOptions {
+    pub window: usize,
+    pub level: f64,
+}
+
+impl Default for DustOptions {
+    fn default() -> Self {
+        DustOptions {
+            window: DUST_WINDOW,
+            level: DUST_LEVEL,
+        }
+    }
+}
+
+// Windows with Shannon entropy (bits) at most locut trigger a low-complexity
+// segment, which is then extended over neighbouring windows up to hicut
+pub fn seg<T: BioData + Into<usize> + Copy>(sequence: &[T], opts: &SegOptions) -> Vec<bool> {
+    let window = opts.window;
+    let mut mask = vec![false; sequence.len()];
+
+    if window == 0 || sequence.len() < window {
+        return mask;
+    }
+
+    let mut counts = vec![0usize; T::volume()];
+    let mut weighted = 0f64;
+    let c_log_c = |c: usize| {
+        if c > 0 {
+            c as f64 * (c as f64).log2()
+        } else {
+            0f64
+        }
+    };
+
+    let mut update = |elem: &T, delta: isize, weighted: &mut f64| {
+        let a: usize = (*elem).into();
+        if a < counts.len() {
+            *weighted -= c_log_c(counts[a]);
+            counts[a] = (counts[a] as isize + delta) as usize;
+            *weighted += c_log_c(counts[a]);
+        }
+    };
+
+    let n = window as f64;
+    let mut entropies = vec![];
+
+    for (i, elem) in sequence.iter().enumerate() {
+        update(elem, 1, &mut weighted);
+
+        if i >= window {
+            update(&sequence[i - window], -1, &mut weighted);
+        }
+
+        if i + 1 >= window {
+            entropies.push(n.log2() - weighted / n);
+        }
+    }
+
+    let mut i = 0;
+    while i < entropies.len() {
+        if entropies[i] > opts.locut {
+            i += 1;
+            continue;
+        }
+
+        let mut left = i;
+        while left > 0 && entropies[left - 1] <= opts.hicut {
+            left -= 1;
+        }
+
+        let mut right = i;
+        while right + 1 < entropies.len() && entropies[right + 1] <= opts.hicut {
+            right += 1;
+        }
+
+        let (start, end) = trim_segment(&sequence[left..right + window]);
+
+        for elem in mask[left + start..left + end].iter_mut() {
+            *elem = true;
+        }
+
+        i = right + 1;
+    }
+
+    mask
+}
+
+// Drops residues from both ends of a segment while they occur only once in it
+fn trim_segment<T: Into<usize> + Copy>(segment: &[T]) -> (usize, usize) {
+    let mut counts = vec![0usize; segment.len()];
+    let codes: Vec<usize> = segment.iter().map(|elem| (*elem).into()).collect();
+
+    for (i, code) in codes.iter().enumerate() {
+        counts[i] = codes.iter().filter(|other| *other == code).count();
+    }
+
+    let (mut start, mut end) = (0, segment.len());
+    while start < end && counts[start] == 1 {
+        start += 1;
+    }
+    while end > start && counts[end - 1] == 1 {
+        end -= 1;
+    }
+
+    (start, end)
+}
+
+// Windowed DUST: a window is masked when its triplet repetition score
+// sum(c * (c - 1) / 2) / (l - 1) exceeds the level
+pub fn dust(sequence: &[DNA], opts: &DustOptions) -> Vec<bool> {
+    let window = opts.window;
+    let mut mask = vec![false; sequence.len()];
+
+    if window < 4 || sequence.len() < window {
+        return mask;
+    }
+
+    let triplets: Vec<Option<usize>> = sequence
+        .windows(3)
+        .map(|triplet| {
+            triplet.iter().try_fold(0usize, |code, elem| {
+                let a: usize = (*elem).into();
+                if a < DNA::volume() {
+                    Some(code * DNA::volume() + a)
+                } else {
+                    None
+                }
+            })
+        })
+        .collect();
+
+    let length = window - 2;
+    let mut counts = [0usize; TRIPLETS];
+    let mut score = 0usize;
+    let mut coverage = vec![0isize; sequence.len() + 1];
+
+    for (i, triplet) in triplets.iter().enumerate() {
+        if let Some(code) = triplet {
+            score += counts[*code];
+            counts[*code] += 1;
+        }
+
+        if i >= length {
+            if let Some(code) = triplets[i - length] {
+                counts[code] -= 1;
+                score -= counts[code];
+            }
+        }
+
+        if i + 1 >= length && score as f64 / (length - 1) as f64 > opts.level {
+            let start = i + 1 - length;
+            coverage[start] += 1;
+            coverage[start + window] -= 1;
+        }
+    }
+
+    let mut depth = 0;
+    for (elem, delta) in mask.iter_mut().zip(coverage.iter()) {
+        depth += delta;
+        *elem = depth > 0;
+    }
+
+    mask
+}
+
+// Replaces residues of a raw sequence with symbol wherever the mask built from
+// its parsed form is set; unparsed bytes are kept in place
+pub fn apply_mask<T: BioData>(raw: &[u8], mask: &[bool], symbol: u8) -> Vec<u8> {
+    let mut result = Vec::from(raw);
+    let mut position = 0;
+
+    for elem in result.iter_mut() {
+        if T::match_with_char(*elem as char).is_err() {
+            continue;
+        }
+
+        if mask.get(position).cloned().unwrap_or(false) {
+            *elem = symbol;
+        }
+
+        position += 1;
+    }
+
+    result
+}
@@ -0,0 +1,37 @@
+use crate::enums::{BioData, Protein, DNA};
+use crate::masking::{apply_mask, dust, seg, DustOptions, SegOptions};
+
+#[test]
+fn seg_masks_low_complexity_protein_test() {
+    let flank = "MKTWDRYQEFHSICNAGVL";
+    let raw = format!("{}{}{}", flank, "P".repeat(20), flank);
+    let sequence = Protein::str_to_vec(&raw).unwrap();
+
+    let mask = seg(&sequence, &SegOptions::default());
+
+    assert!(mask[flank.len()..flank.len() + 20].iter().all(|elem| *elem));
+    assert_eq!(mask.iter().filter(|elem| **elem).count(), 20);
+}
+
+#[test]
+fn dust_masks_homopolymer_test() {
+    let flank = "ATGCTTGACCGTAGCATCGGATCCTAGGCTATCGATTGCAAGCTCGTAGGCTAACGTTGCAGTCAGGATCCAT";
+    let raw = format!("{}{}{}", flank, "A".repeat(100), flank);
+    let sequence = DNA::str_to_vec(&raw).unwrap();
+
+    let mask = dust(&sequence, &DustOptions::default());
+
+    assert!(mask[flank.len()..flank.len() + 100]
+        .iter()
+        .all(|elem| *elem));
+    assert!(mask[..10].iter().all(|elem| !*elem));
+    assert!(mask[mask.len() - 10..].iter().all(|elem| !*elem));
+}
+
+#[test]
+fn apply_mask_skips_unparsed_bytes_test() {
+    let raw = b"ACG\nTTA\nN";
+    let mask = vec![false, true, true, false, true, false];
+
+    assert_eq!(apply_mask::<DNA>(raw, &mask, b'N'), b"ANN\nTNA\nN".to_vec());
+}
@@ -0,0 +1,118 @@
+use crate::{get_blosum62, get_dna_matrix, BioData, Error, Result};
+use ndarray::Array2;
+use std::path::Path;
+
+#[cfg(test)]
+mod test;
+
+// Default blastn reward and penalty
+const BLASTN_MATCH: f64 = 2f64;
+const BLASTN_MISMATCH: f64 = -3f64;
+
+// Built-in matrices, callers check that the matrix fits their alphabet
+pub fn get_matrix_by_name(name: &str) -> Result<Array2<f64>> {
+    match name.to_lowercase().as_str() {
+        "blosum62" => Ok(get_blosum62()),
+        "blastn" => Ok(get_dna_matrix(BLASTN_MATCH, BLASTN_MISMATCH)),
+        _ => {
+            error!("Unknown matrix {}", name);
+            Err(Error::ValidationError)
+        }
+    }
+}
+
+// Whitespace separated scores, either a bare grid in the alphabet order or an
+// NCBI-style table with residues in the header and at the start of every row.
+// Residues out of the alphabet (e.g. '*') are skipped
+pub fn read_matrix<T: BioData + Into<usize>>(contents: &str) -> Result<Array2<f64>> {
+    let mut lines = contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .peekable();
+
+    let header: Option<Vec<char>> = match lines.peek() {
+        Some(line)
+            if line
+                .split_whitespace()
+                .next()
+                .unwrap()
+                .parse::<f64>()
+                .is_err() =>
+        {
+            let header = line
+                .split_whitespace()
+                .map(|token| token.chars().next().unwrap())
+                .collect();
+            lines.next();
+            Some(header)
+        }
+        Some(_) => None,
+        None => return Err(Error::ParseError),
+    };
+
+    let mut result = Array2::<f64>::zeros((T::volume(), T::volume()));
+    let mut rows = 0;
+
+    for (y, line) in lines.enumerate() {
+        rows += 1;
+        let mut tokens = line.split_whitespace();
+
+        let row = match header {
+            Some(_) => match tokens.next().and_then(|token| token.chars().next()) {
+                Some(residue) => get_index::<T>(residue),
+                None => return Err(Error::ParseError),
+            },
+            None => Some(y),
+        };
+
+        let values = tokens
+            .map(|token| token.parse::<f64>())
+            .collect::<std::result::Result<Vec<f64>, _>>()
+            .map_err(|_| Error::ParseError)?;
+
+        let columns: Vec<Option<usize>> = match &header {
+            Some(header) => header
+                .iter()
+                .map(|residue| get_index::<T>(*residue))
+                .collect(),
+            None => (0..values.len()).map(Some).collect(),
+        };
+
+        if values.len() != columns.len() || (header.is_none() && y >= T::volume()) {
+            error!("Row {} of the matrix doesn't fit the alphabet", y + 1);
+            return Err(Error::MatrixShapeError);
+        }
+
+        for (column, value) in columns.into_iter().zip(values) {
+            if let (Some(row), Some(column)) = (row, column) {
+                result[[row, column]] = value;
+            }
+        }
+    }
+
+    // A bare grid has to cover the whole alphabet
+    if header.is_none() && rows != T::volume() {
+        error!("The matrix has {} rows instead of {}", rows, T::volume());
+        return Err(Error::MatrixShapeError);
+    }
+
+    Ok(result)
+}
+
+pub fn load_matrix<T: BioData + Into<usize>>(path: &Path) -> Result<Array2<f64>> {
+    match std::fs::read_to_string(path) {
+        Ok(contents) => read_matrix::<T>(&contents),
+        Err(err) => {
+            error!("Unable to read {}: {}", path.display(), err);
+            Err(Error::IOError)
+        }
+    }
+}
+
+fn get_index<T: BioData + Into<usize>>(residue: char) -> Option<usize> {
+    T::match_with_char(residue.to_ascii_uppercase())
+        .ok()
+        .map(|value| value.into())
+        .filter(|index| *index < T::volume())
+}
@@ -0,0 +1,47 @@
+use crate::enums::{Protein, DNA};
+use crate::matrices::{get_matrix_by_name, read_matrix};
+use crate::{get_blosum62, Error};
+
+#[test]
+fn read_matrix_test() {
+    let grid = "# bare grid\n\
+                 1 -1 -2 -2\n\
+                 -1 1 -2 -2\n\
+                 -2 -2 1 -1\n\
+                 -2 -2 -1 1\n";
+    let matrix = read_matrix::<DNA>(grid).unwrap();
+    assert_eq!(matrix[[0, 0]], 1f64);
+    assert_eq!(matrix[[2, 0]], -2f64);
+    assert_eq!(matrix[[3, 2]], -1f64);
+
+    // Header order differs from the alphabet, N and * are skipped
+    let table = "   T  A  G  C  N\n\
+                 T  5 -4 -4 -4 -2\n\
+                 A -4  5 -4 -4 -2\n\
+                 G -4 -4  5 -4 -2\n\
+                 C -4 -4 -4  5 -2\n\
+                 * -2 -2 -2 -2 -2\n";
+    let matrix = read_matrix::<DNA>(table).unwrap();
+    assert_eq!(matrix[[DNA::A as usize, DNA::A as usize]], 5f64);
+    assert_eq!(matrix[[DNA::T as usize, DNA::G as usize]], -4f64);
+
+    assert!(matches!(
+        read_matrix::<DNA>("1 -1\n-1 1\n"),
+        Err(Error::MatrixShapeError)
+    ));
+    assert!(matches!(
+        read_matrix::<DNA>("   A  T\nA  1\n"),
+        Err(Error::MatrixShapeError)
+    ));
+    assert!(matches!(
+        read_matrix::<Protein>(grid),
+        Err(Error::MatrixShapeError)
+    ));
+}
+
+#[test]
+fn matrix_by_name_test() {
+    assert_eq!(get_matrix_by_name("BLOSUM62").unwrap(), get_blosum62());
+    assert_eq!(get_matrix_by_name("blastn").unwrap()[[0, 1]], -3f64);
+    assert!(get_matrix_by_name("pam30").is_err());
+}
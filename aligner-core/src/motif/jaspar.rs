@@ -0,0 +1,103 @@
+use crate::motif::{format_value, letter_to_row, parse_values, Motif, ACGT};
+use crate::{Error, Result};
+use ndarray::Array2;
+
+pub(crate) fn read(contents: &str) -> Result<Vec<Motif>> {
+    let mut motifs = vec![];
+    let mut name: Option<String> = None;
+    let mut rows: Vec<(usize, Vec<f64>)> = vec![];
+
+    for line in contents.lines() {
+        let line = line.trim();
+
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(header) = line.strip_prefix('>') {
+            if !rows.is_empty() {
+                motifs.push(build_motif(name.take(), &rows, motifs.len())?);
+                rows.clear();
+            }
+
+            name = Some(header.split_whitespace().collect::<Vec<&str>>().join("_"));
+            continue;
+        }
+
+        let (letter, values) = match line.find(|c: char| c.is_ascii_alphabetic()) {
+            Some(0) => {
+                let (letter, values) = line.split_at(1);
+                (Some(letter), values)
+            }
+            _ => (None, line),
+        };
+
+        let row = match letter {
+            Some(letter) => letter_to_row(letter)?,
+            None => match ACGT.get(rows.len()) {
+                Some(dna) => usize::from(*dna),
+                None => return Err(Error::ParseError),
+            },
+        };
+
+        rows.push((row, parse_values(&values.replace(['[', ']'], " "))?));
+
+        if rows.len() == ACGT.len() && name.is_none() {
+            motifs.push(build_motif(None, &rows, motifs.len())?);
+            rows.clear();
+        }
+    }
+
+    if !rows.is_empty() {
+        motifs.push(build_motif(name, &rows, motifs.len())?);
+    }
+
+    Ok(motifs)
+}
+
+pub(crate) fn write(motifs: &[Motif]) -> String {
+    let mut result = String::new();
+
+    for motif in motifs.iter() {
+        result += &format!(">{}\n", motif.name);
+
+        for (letter, dna) in "ACGT".chars().zip(ACGT.iter()) {
+            let row: Vec<String> = motif
+                .counts
+                .row(usize::from(*dna))
+                .iter()
+                .map(|value| format_value(*value))
+                .collect();
+
+            result += &format!("{}  [ {} ]\n", letter, row.join(" "));
+        }
+    }
+
+    result
+}
+
+fn build_motif(name: Option<String>, rows: &[(usize, Vec<f64>)], index: usize) -> Result<Motif> {
+    if rows.len() != ACGT.len() {
+        return Err(Error::ParseError);
+    }
+
+    let width = rows[0].1.len();
+    let mut counts = Array2::<f64>::zeros((ACGT.len(), width));
+
+    for (row, values) in rows.iter() {
+        if values.len() != width {
+            return Err(Error::ParseError);
+        }
+
+        for (position, value) in values.iter().enumerate() {
+            counts[[*row, position]] = *value;
+        }
+    }
+
+    let name = match name {
+        Some(name) => name,
+        None => format!("motif_{}", index + 1),
+    };
+
+    Motif::new(&name, counts)
+}
@@ -0,0 +1,129 @@
+use crate::motif::{format_value, letter_to_row, parse_values, Motif, ACGT};
+use crate::{Error, Result};
+use ndarray::Array2;
+
+const DEFAULT_SITES: f64 = 20f64;
+
+pub(crate) fn read(contents: &str) -> Result<Vec<Motif>> {
+    let mut rows = vec![];
+    for dna in ACGT.iter() {
+        rows.push(usize::from(*dna));
+    }
+
+    let mut motifs = vec![];
+    let mut name = None;
+    let mut lines = contents.lines();
+
+    while let Some(line) = lines.next() {
+        let line = line.trim();
+
+        if let Some(alphabet) = line.strip_prefix("ALPHABET=") {
+            let alphabet = alphabet.trim();
+
+            if alphabet.len() != ACGT.len() {
+                return Err(Error::ParseError);
+            }
+
+            rows = vec![];
+            for letter in alphabet.chars() {
+                rows.push(letter_to_row(&letter.to_string())?);
+            }
+        } else if let Some(header) = line.strip_prefix("MOTIF") {
+            name = header
+                .split_whitespace()
+                .next()
+                .map(|name| name.to_string());
+        } else if let Some(header) = line.strip_prefix("letter-probability matrix:") {
+            let width = match header_value(header, "w=") {
+                Some(width) => width as usize,
+                None => return Err(Error::ParseError),
+            };
+            let sites = header_value(header, "nsites=").unwrap_or(DEFAULT_SITES);
+
+            let mut counts = Array2::<f64>::zeros((ACGT.len(), width));
+
+            let mut position = 0;
+            while position < width {
+                let row = match lines.next() {
+                    Some(row) => row.trim(),
+                    None => return Err(Error::ParseError),
+                };
+
+                if row.is_empty() {
+                    continue;
+                }
+
+                let values = parse_values(row)?;
+                if values.len() != rows.len() {
+                    return Err(Error::ParseError);
+                }
+
+                for (value, dna) in values.iter().zip(rows.iter()) {
+                    counts[[*dna, position]] = value * sites;
+                }
+
+                position += 1;
+            }
+
+            let motif_name = match name.take() {
+                Some(name) => name,
+                None => format!("motif_{}", motifs.len() + 1),
+            };
+
+            motifs.push(Motif::new(&motif_name, counts)?);
+        }
+    }
+
+    Ok(motifs)
+}
+
+pub(crate) fn write(motifs: &[Motif]) -> String {
+    let mut result = String::from("MEME version 4\n\nALPHABET= ACGT\n\nstrands: + -\n\n");
+
+    result += "Background letter frequencies\nA 0.25 C 0.25 G 0.25 T 0.25\n";
+
+    for motif in motifs.iter() {
+        let probabilities = motif.probabilities();
+
+        result += &format!(
+            "\nMOTIF {}\nletter-probability matrix: alength= {} w= {} nsites= {} E= 0\n",
+            motif
+                .name
+                .split_whitespace()
+                .collect::<Vec<&str>>()
+                .join("_"),
+            ACGT.len(),
+            motif.length(),
+            format_value(motif.sites())
+        );
+
+        for position in 0..motif.length() {
+            let row: Vec<String> = ACGT
+                .iter()
+                .map(|dna| format!("{:.6}", probabilities[[usize::from(*dna), position]]))
+                .collect();
+
+            result += &format!(" {}\n", row.join(" "));
+        }
+    }
+
+    result
+}
+
+fn header_value(header: &str, key: &str) -> Option<f64> {
+    let mut tokens = header.split_whitespace();
+
+    while let Some(token) = tokens.next() {
+        if let Some(value) = token.strip_prefix(key) {
+            let value = if value.is_empty() {
+                tokens.next()?
+            } else {
+                value
+            };
+
+            return value.parse::<f64>().ok();
+        }
+    }
+
+    None
+}
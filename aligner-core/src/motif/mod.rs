@@ -0,0 +1,150 @@
+use crate::enums::{BioData, DNA};
+use crate::{Error, Result};
+use ndarray::{Array2, Axis};
+use std::path::Path;
+use std::str::FromStr;
+
+mod jaspar;
+mod meme;
+#[cfg(test)]
+mod test;
+mod transfac;
+
+// Usual column order of motif formats, rows of Motif follow DNA enum order (A, T, C, G)
+const ACGT: [DNA; 4] = [DNA::A, DNA::C, DNA::G, DNA::T];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MotifFormat {
+    Meme,
+    Jaspar,
+    Transfac,
+}
+
+impl FromStr for MotifFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<MotifFormat> {
+        match s.to_lowercase().as_str() {
+            "meme" => Ok(MotifFormat::Meme),
+            "jaspar" => Ok(MotifFormat::Jaspar),
+            "transfac" => Ok(MotifFormat::Transfac),
+            _ => Err(Error::ValidationError),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Motif {
+    pub name: String,
+    pub counts: Array2<f64>,
+}
+
+impl Motif {
+    pub fn new(name: &str, counts: Array2<f64>) -> Result<Motif> {
+        if counts.len_of(Axis(0)) != DNA::volume() {
+            return Err(Error::MatrixShapeError);
+        }
+
+        Ok(Motif {
+            name: name.to_string(),
+            counts,
+        })
+    }
+
+    pub fn length(&self) -> usize {
+        self.counts.len_of(Axis(1))
+    }
+
+    pub fn sites(&self) -> f64 {
+        match self.counts.axis_iter(Axis(1)).next() {
+            Some(column) => column.sum(),
+            None => 0f64,
+        }
+    }
+
+    pub fn probabilities(&self) -> Array2<f64> {
+        let mut result = self.counts.clone();
+
+        for mut column in result.axis_iter_mut(Axis(1)) {
+            let total = column.sum();
+
+            if total > 0f64 {
+                column /= total;
+            } else {
+                column.fill(1f64 / DNA::volume() as f64);
+            }
+        }
+
+        result
+    }
+}
+
+pub fn read_motifs(contents: &str, format: MotifFormat) -> Result<Vec<Motif>> {
+    let motifs = match format {
+        MotifFormat::Meme => meme::read(contents)?,
+        MotifFormat::Jaspar => jaspar::read(contents)?,
+        MotifFormat::Transfac => transfac::read(contents)?,
+    };
+
+    if motifs.is_empty() {
+        return Err(Error::ResultIsEmpty);
+    }
+
+    Ok(motifs)
+}
+
+pub fn write_motifs(motifs: &[Motif], format: MotifFormat) -> String {
+    match format {
+        MotifFormat::Meme => meme::write(motifs),
+        MotifFormat::Jaspar => jaspar::write(motifs),
+        MotifFormat::Transfac => transfac::write(motifs),
+    }
+}
+
+pub fn load_motifs(path: &Path, format: MotifFormat) -> Result<Vec<Motif>> {
+    match std::fs::read_to_string(path) {
+        Ok(contents) => read_motifs(&contents, format),
+        Err(_) => Err(Error::IOError),
+    }
+}
+
+pub fn save_motifs(path: &Path, motifs: &[Motif], format: MotifFormat) -> Result<()> {
+    match std::fs::write(path, write_motifs(motifs, format)) {
+        Ok(_) => Ok(()),
+        Err(_) => Err(Error::IOError),
+    }
+}
+
+fn letter_to_row(letter: &str) -> Result<usize> {
+    let mut chars = letter.chars();
+
+    match (chars.next(), chars.next()) {
+        (Some(symbol), None) => match DNA::match_with_char(symbol.to_ascii_uppercase()) {
+            Ok(DNA::Blank) | Ok(DNA::Pos) | Ok(DNA::Any) => Err(Error::ParseError),
+            Ok(dna) => Ok(dna.into()),
+            Err(_) => Err(Error::ParseError),
+        },
+        _ => Err(Error::ParseError),
+    }
+}
+
+fn parse_values(line: &str) -> Result<Vec<f64>> {
+    let mut result = vec![];
+
+    for value in line.split_whitespace() {
+        result.push(match value.parse::<f64>() {
+            Ok(value) => value,
+            Err(_) => return Err(Error::ParseError),
+        });
+    }
+
+    Ok(result)
+}
+
+fn format_value(value: f64) -> String {
+    if value.fract() == 0f64 {
+        format!("{}", value as i64)
+    } else {
+        format!("{:.6}", value)
+    }
+}
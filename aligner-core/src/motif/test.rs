@@ -0,0 +1,40 @@
+use crate::enums::DNA;
+use crate::motif::{read_motifs, write_motifs, MotifFormat};
+
+const JASPAR: &str = ">MA0004.1 Arnt
+A  [ 4 19  0  0  0  0 ]
+C  [16  0 20  0  0  0 ]
+G  [ 0  1  0 20  0 20 ]
+T  [ 0  0  0  0 20  0 ]
+";
+
+#[test]
+fn jaspar_rows_follow_dna_order_test() {
+    let motifs = read_motifs(JASPAR, MotifFormat::Jaspar).unwrap();
+
+    assert_eq!(motifs.len(), 1);
+    assert_eq!(motifs[0].name, "MA0004.1_Arnt");
+    assert_eq!(motifs[0].counts[[DNA::A as usize, 1]], 19f64);
+    assert_eq!(motifs[0].counts[[DNA::C as usize, 0]], 16f64);
+    assert_eq!(motifs[0].counts[[DNA::G as usize, 3]], 20f64);
+    assert_eq!(motifs[0].counts[[DNA::T as usize, 4]], 20f64);
+}
+
+#[test]
+fn motif_formats_round_trip_test() {
+    let motifs = read_motifs(JASPAR, MotifFormat::Jaspar).unwrap();
+
+    for format in [
+        MotifFormat::Meme,
+        MotifFormat::Jaspar,
+        MotifFormat::Transfac,
+    ] {
+        let result = read_motifs(&write_motifs(&motifs, format), format).unwrap();
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].name, motifs[0].name);
+        assert!((&result[0].counts - &motifs[0].counts)
+            .iter()
+            .all(|value| value.abs() < 1e-4));
+    }
+}
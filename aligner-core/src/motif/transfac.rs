@@ -0,0 +1,118 @@
+use crate::motif::{format_value, letter_to_row, parse_values, Motif, ACGT};
+use crate::{Error, Result};
+use ndarray::Array2;
+
+pub(crate) fn read(contents: &str) -> Result<Vec<Motif>> {
+    let mut motifs = vec![];
+
+    let mut accession: Option<String> = None;
+    let mut identifier: Option<String> = None;
+    let mut rows: Option<Vec<usize>> = None;
+    let mut positions: Vec<Vec<f64>> = vec![];
+
+    for line in contents.lines() {
+        let line = line.trim_end();
+
+        if line.starts_with("//") {
+            if let Some(rows) = rows.take() {
+                let name = match identifier.take().or_else(|| accession.take()) {
+                    Some(name) => name,
+                    None => format!("motif_{}", motifs.len() + 1),
+                };
+
+                motifs.push(build_motif(&name, &rows, &positions)?);
+            }
+
+            accession = None;
+            identifier = None;
+            positions.clear();
+            continue;
+        }
+
+        let (code, value) = match line.split_once(char::is_whitespace) {
+            Some((code, value)) => (code, value.trim()),
+            None => (line, ""),
+        };
+
+        match code {
+            "AC" => accession = Some(value.to_string()),
+            "ID" | "NA" if identifier.is_none() => identifier = Some(value.to_string()),
+            "P0" | "PO" => {
+                let mut letters = vec![];
+                for letter in value.split_whitespace() {
+                    letters.push(letter_to_row(letter)?);
+                }
+
+                if letters.len() != ACGT.len() {
+                    return Err(Error::ParseError);
+                }
+
+                rows = Some(letters);
+            }
+            _ if rows.is_some() && !code.is_empty() && code.chars().all(|c| c.is_ascii_digit()) => {
+                let mut values = value.split_whitespace().collect::<Vec<&str>>();
+
+                if values.len() == ACGT.len() + 1 {
+                    values.pop();
+                }
+
+                positions.push(parse_values(&values.join(" "))?);
+            }
+            _ => {}
+        }
+    }
+
+    if let Some(rows) = rows {
+        let name = match identifier.or(accession) {
+            Some(name) => name,
+            None => format!("motif_{}", motifs.len() + 1),
+        };
+
+        motifs.push(build_motif(&name, &rows, &positions)?);
+    }
+
+    Ok(motifs)
+}
+
+pub(crate) fn write(motifs: &[Motif]) -> String {
+    let mut result = String::new();
+
+    for motif in motifs.iter() {
+        result += &format!("AC  {}\nXX\nID  {}\nXX\n", motif.name, motif.name);
+        result += "P0      A      C      G      T\n";
+
+        for position in 0..motif.length() {
+            let row: Vec<String> = ACGT
+                .iter()
+                .map(|dna| {
+                    format!(
+                        "{:>6}",
+                        format_value(motif.counts[[usize::from(*dna), position]])
+                    )
+                })
+                .collect();
+
+            result += &format!("{:02} {}\n", position + 1, row.join(" "));
+        }
+
+        result += "XX\n//\n";
+    }
+
+    result
+}
+
+fn build_motif(name: &str, rows: &[usize], positions: &[Vec<f64>]) -> Result<Motif> {
+    let mut counts = Array2::<f64>::zeros((ACGT.len(), positions.len()));
+
+    for (position, values) in positions.iter().enumerate() {
+        if values.len() != rows.len() {
+            return Err(Error::ParseError);
+        }
+
+        for (value, row) in values.iter().zip(rows.iter()) {
+            counts[[*row, position]] = *value;
+        }
+    }
+
+    Motif::new(name, counts)
+}
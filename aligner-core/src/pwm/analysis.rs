@@ -0,0 +1,237 @@
+use crate::{BioData, Error, Result};
+use ndarray::{Array1, Array2, Axis};
+use std::marker::PhantomData;
+
+const COLUMN_WIDTH: f64 = 30f64;
+const BIT_HEIGHT: f64 = 50f64;
+const MARGIN: f64 = 40f64;
+// Cap height of a letter relative to its font size in common sans-serif fonts
+const CAP_HEIGHT: f64 = 0.72;
+
+#[derive(Debug, Clone)]
+pub struct PWMProfile<T: BioData + Into<usize> + From<usize> + Copy + Eq> {
+    pub probabilities: Array2<f64>,
+    pub background: Array1<f64>,
+    phantom: PhantomData<T>,
+}
+
+impl<T: BioData + Into<usize> + From<usize> + Copy + Eq> PWMProfile<T> {
+    pub fn uniform_background() -> Array1<f64> {
+        Array1::<f64>::from_elem(T::volume(), 1f64 / T::volume() as f64)
+    }
+
+    pub fn from_counts(
+        counts: &Array2<f64>,
+        background: &Array1<f64>,
+        pseudocount: f64,
+    ) -> Result<PWMProfile<T>> {
+        let background = validate(counts, background)?;
+
+        if pseudocount < 0f64 {
+            return Err(Error::ValidationError);
+        }
+
+        let mut probabilities = counts.clone();
+
+        for mut column in probabilities.axis_iter_mut(Axis(1)) {
+            let total = column.sum() + pseudocount;
+
+            if total <= 0f64 {
+                column.assign(&background);
+                continue;
+            }
+
+            for (a, value) in column.iter_mut().enumerate() {
+                *value = (*value + pseudocount * background[a]) / total;
+            }
+        }
+
+        Ok(PWMProfile {
+            probabilities,
+            background,
+            phantom: PhantomData,
+        })
+    }
+
+    pub fn from_scores(scores: &Array2<f64>, background: &Array1<f64>) -> Result<PWMProfile<T>> {
+        let background = validate(scores, background)?;
+
+        let mut probabilities = scores.clone();
+
+        for mut column in probabilities.axis_iter_mut(Axis(1)) {
+            let max = column.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+
+            // Compared first, as infinite scores give NaN when subtracted
+            for (a, value) in column.iter_mut().enumerate() {
+                let weight = match *value == max {
+                    true => 1f64,
+                    false => (*value - max).exp(),
+                };
+                *value = background[a] * weight;
+            }
+
+            let total = column.sum();
+
+            if total > 0f64 {
+                column /= total;
+            } else {
+                column.assign(&background);
+            }
+        }
+
+        Ok(PWMProfile {
+            probabilities,
+            background,
+            phantom: PhantomData,
+        })
+    }
+
+    pub fn length(&self) -> usize {
+        self.probabilities.len_of(Axis(1))
+    }
+
+    pub fn information_content(&self) -> Array1<f64> {
+        self.probabilities.map_axis(Axis(0), |column| {
+            column
+                .iter()
+                .zip(self.background.iter())
+                .filter(|(p, _)| **p > 0f64)
+                .map(|(p, b)| p * (p / b).log2())
+                .sum()
+        })
+    }
+
+    pub fn consensus(&self) -> Vec<T> {
+        let mut result = vec![];
+
+        for column in self.probabilities.axis_iter(Axis(1)) {
+            let mut best = 0;
+            for (a, value) in column.iter().enumerate() {
+                if *value > column[best] {
+                    best = a;
+                }
+            }
+
+            result.push(best.into());
+        }
+
+        result
+    }
+
+    pub fn consensus_string(&self) -> Result<String> {
+        T::vec_to_str(&self.consensus())
+    }
+
+    pub fn logo_svg(&self) -> Result<String> {
+        let information_content = self.information_content();
+
+        let max_bits = self
+            .background
+            .iter()
+            .map(|b| -b.log2())
+            .fold(0f64, f64::max)
+            .ceil();
+
+        let width = MARGIN * 1.5 + COLUMN_WIDTH * self.length() as f64;
+        let height = MARGIN * 1.5 + BIT_HEIGHT * max_bits;
+        let baseline = MARGIN / 2f64 + BIT_HEIGHT * max_bits;
+
+        let mut result = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\">\n",
+            width, height, width, height
+        );
+
+        result += &format!(
+            "<line x1=\"{m}\" y1=\"{t}\" x2=\"{m}\" y2=\"{b}\" stroke=\"black\"/>\n",
+            m = MARGIN,
+            t = MARGIN / 2f64,
+            b = baseline
+        );
+
+        for bit in 0..=(max_bits as usize) {
+            let y = baseline - bit as f64 * BIT_HEIGHT;
+
+            result += &format!(
+                "<line x1=\"{}\" y1=\"{y}\" x2=\"{}\" y2=\"{y}\" stroke=\"black\"/>\n\
+                 <text x=\"{}\" y=\"{}\" font-size=\"10\" text-anchor=\"end\">{}</text>\n",
+                MARGIN - 4f64,
+                MARGIN,
+                MARGIN - 6f64,
+                y + 3f64,
+                bit,
+                y = y
+            );
+        }
+
+        result += &format!(
+            "<text x=\"10\" y=\"{y}\" font-size=\"10\" transform=\"rotate(-90 10 {y})\" text-anchor=\"middle\">bits</text>\n",
+            y = baseline - BIT_HEIGHT * max_bits / 2f64
+        );
+
+        for (position, column) in self.probabilities.axis_iter(Axis(1)).enumerate() {
+            let x = MARGIN + COLUMN_WIDTH * position as f64;
+
+            let mut letters: Vec<(usize, f64)> = column
+                .iter()
+                .enumerate()
+                .map(|(a, p)| (a, p * information_content[position]))
+                .filter(|(_, letter_height)| *letter_height > 0f64)
+                .collect();
+            letters.sort_by(|u, v| u.1.total_cmp(&v.1));
+
+            let mut y = baseline;
+
+            for (a, letter_height) in letters.iter() {
+                let symbol = T::convert_to_char(&T::from(*a))?;
+                let pixels = letter_height * BIT_HEIGHT;
+
+                result += &format!(
+                    "<text x=\"0\" y=\"0\" font-family=\"sans-serif\" font-weight=\"bold\" font-size=\"100\" \
+                     fill=\"{}\" textLength=\"{}\" lengthAdjust=\"spacingAndGlyphs\" \
+                     transform=\"translate({:.3} {:.3}) scale(1 {:.5})\">{}</text>\n",
+                    letter_color(symbol),
+                    COLUMN_WIDTH,
+                    x,
+                    y,
+                    pixels / (100f64 * CAP_HEIGHT),
+                    symbol
+                );
+
+                y -= pixels;
+            }
+
+            result += &format!(
+                "<text x=\"{}\" y=\"{}\" font-size=\"10\" text-anchor=\"middle\">{}</text>\n",
+                x + COLUMN_WIDTH / 2f64,
+                baseline + 12f64,
+                position + 1
+            );
+        }
+
+        result += "</svg>\n";
+
+        Ok(result)
+    }
+}
+
+fn validate(matrix: &Array2<f64>, background: &Array1<f64>) -> Result<Array1<f64>> {
+    if matrix.len_of(Axis(0)) != background.len() {
+        return Err(Error::MatrixShapeError);
+    }
+
+    if background.iter().any(|b| *b <= 0f64) {
+        return Err(Error::ValidationError);
+    }
+
+    Ok(background / background.sum())
+}
+
+fn letter_color(symbol: char) -> &'static str {
+    match symbol {
+        'A' => "#109648",
+        'C' => "#255C99",
+        'G' => "#F7B32B",
+        'T' => "#D62839",
+        _ => "#000000",
+    }
+}
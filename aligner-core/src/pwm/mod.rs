@@ -6,6 +6,10 @@ use ndarray::Array2;
 use ndarray_stats::QuantileExt;
 use std::marker::PhantomData;
 
+pub mod analysis;
+#[cfg(test)]
+mod test;
+
 pub struct PWMAligner<T: BioData + Into<usize> + Copy + Eq> {
     pub query: Vec<T>,
 }
@@ -37,7 +41,7 @@ impl<T: BioData + Into<usize> + Copy + Eq> AlignerTrait<T, PWMAlignment<T>> for
             return Err(Error::UnnecessaryArgument);
         };
 
-        if matrix.shape()[0] != 4 {
+        if matrix.shape()[0] != T::volume() {
             return Err(Error::MatrixShapeError);
         }
 
@@ -0,0 +1,156 @@
+use crate::enums::{Protein, DNA};
+use crate::pwm::analysis::PWMProfile;
+use crate::pwm::PWMAligner;
+use crate::{get_random_pwm, get_rng, AlignerTrait, Error};
+use ndarray::{arr1, arr2, Axis};
+
+fn assert_close(value: f64, expected: f64) {
+    assert!(
+        (value - expected).abs() < 1e-12,
+        "{} != {}",
+        value,
+        expected
+    );
+}
+
+#[test]
+fn from_counts_test() {
+    // Rows are A, T, C, G
+    let counts = arr2(&[
+        [8f64, 2f64, 0f64],
+        [0f64, 2f64, 0f64],
+        [0f64, 2f64, 0f64],
+        [0f64, 2f64, 0f64],
+    ]);
+    let background = arr1(&[4f64, 1f64, 1f64, 4f64]);
+
+    let profile = PWMProfile::<DNA>::from_counts(&counts, &background, 4f64).unwrap();
+
+    // The background is normalised before pseudocounts are spread over it
+    assert_eq!(profile.background, arr1(&[0.4, 0.1, 0.1, 0.4]));
+    assert_close(profile.probabilities[[0, 0]], 9.6 / 12f64);
+    assert_close(profile.probabilities[[1, 0]], 0.4 / 12f64);
+    assert_close(profile.probabilities[[3, 1]], 3.6 / 12f64);
+    for column in profile.probabilities.axis_iter(Axis(1)) {
+        assert_close(column.sum(), 1f64);
+    }
+
+    // Empty columns without pseudocounts fall back to the background
+    let profile = PWMProfile::<DNA>::from_counts(&counts, &background, 0f64).unwrap();
+    assert_eq!(profile.probabilities.column(2), profile.background);
+
+    assert!(matches!(
+        PWMProfile::<DNA>::from_counts(&counts, &background, -1f64),
+        Err(Error::ValidationError)
+    ));
+    assert!(matches!(
+        PWMProfile::<DNA>::from_counts(&counts, &arr1(&[1f64, 1f64, 1f64]), 1f64),
+        Err(Error::MatrixShapeError)
+    ));
+    assert!(matches!(
+        PWMProfile::<DNA>::from_counts(&counts, &arr1(&[1f64, 1f64, 1f64, 0f64]), 1f64),
+        Err(Error::ValidationError)
+    ));
+}
+
+#[test]
+fn information_content_test() {
+    let counts = arr2(&[
+        [10f64, 1f64, 0f64],
+        [0f64, 1f64, 0f64],
+        [0f64, 1f64, 5f64],
+        [0f64, 1f64, 5f64],
+    ]);
+    let profile =
+        PWMProfile::<DNA>::from_counts(&counts, &PWMProfile::<DNA>::uniform_background(), 0f64)
+            .unwrap();
+
+    let information_content = profile.information_content();
+    assert_close(information_content[0], 2f64);
+    assert_close(information_content[1], 0f64);
+    assert_close(information_content[2], 1f64);
+}
+
+#[test]
+fn consensus_test() {
+    let counts = arr2(&[
+        [10f64, 1f64, 0f64],
+        [0f64, 1f64, 0f64],
+        [0f64, 1f64, 2f64],
+        [0f64, 7f64, 5f64],
+    ]);
+    let profile =
+        PWMProfile::<DNA>::from_counts(&counts, &PWMProfile::<DNA>::uniform_background(), 1f64)
+            .unwrap();
+
+    assert_eq!(profile.consensus(), vec![DNA::A, DNA::G, DNA::G]);
+    assert_eq!(profile.consensus_string().unwrap(), "AGG");
+}
+
+#[test]
+fn from_scores_test() {
+    let scores = arr2(&[
+        [2f64.ln(), f64::NEG_INFINITY],
+        [0f64, f64::NEG_INFINITY],
+        [0f64, f64::NEG_INFINITY],
+        [0f64, f64::NEG_INFINITY],
+    ]);
+    let profile =
+        PWMProfile::<DNA>::from_scores(&scores, &PWMProfile::<DNA>::uniform_background()).unwrap();
+
+    assert_close(profile.probabilities[[0, 0]], 0.4);
+    assert_close(profile.probabilities[[1, 0]], 0.2);
+    // A column without finite scores carries no information
+    assert_eq!(profile.probabilities.column(1), profile.background);
+    assert!(profile.probabilities.iter().all(|p| p.is_finite()));
+    assert!(profile.logo_svg().is_ok());
+}
+
+#[test]
+fn logo_svg_test() {
+    let counts = arr2(&[[10f64, 0f64], [0f64, 0f64], [0f64, 5f64], [0f64, 5f64]]);
+    let profile =
+        PWMProfile::<DNA>::from_counts(&counts, &PWMProfile::<DNA>::uniform_background(), 0f64)
+            .unwrap();
+
+    let svg = profile.logo_svg().unwrap();
+
+    assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+    assert!(svg.ends_with("</svg>\n"));
+    // A full column of A, and C and G at half height in the second one
+    assert_eq!(svg.matches(">A</text>").count(), 1);
+    assert_eq!(svg.matches(">C</text>").count(), 1);
+    assert_eq!(svg.matches(">G</text>").count(), 1);
+    assert_eq!(svg.matches(">T</text>").count(), 0);
+    assert!(svg.contains("fill=\"#109648\""));
+    assert!(svg.contains("scale(1 1.38889)"));
+}
+
+#[test]
+fn alignment_volume_test() {
+    let mut rng = get_rng(1);
+    let protein_pwm = get_random_pwm::<Protein, _>(3, &mut rng);
+    let dna_pwm = get_random_pwm::<DNA, _>(3, &mut rng);
+
+    // A row per residue of the alphabet
+    assert_eq!(protein_pwm.shape(), &[24, 3]);
+    assert_eq!(dna_pwm.shape(), &[4, 3]);
+
+    let mut aligner = PWMAligner::<Protein>::from_str_seqs("ARNDC", "").unwrap();
+    assert!(aligner
+        .perform_alignment(1f64, 1f64, &protein_pwm, None)
+        .is_ok());
+    assert!(matches!(
+        aligner.perform_alignment(1f64, 1f64, &dna_pwm, None),
+        Err(Error::MatrixShapeError)
+    ));
+
+    let mut aligner = PWMAligner::<DNA>::from_str_seqs("ACGTA", "").unwrap();
+    assert!(aligner
+        .perform_alignment(1f64, 1f64, &dna_pwm, None)
+        .is_ok());
+    assert!(matches!(
+        aligner.perform_alignment(1f64, 1f64, &protein_pwm, None),
+        Err(Error::MatrixShapeError)
+    ));
+}
@@ -0,0 +1,298 @@
+use super::{SequenceFormat, SequenceReader, SequenceRecord};
+use crate::enums::Strand;
+use crate::Result;
+
+// Feature keys start at column 5, locations and qualifiers at column 21 in
+// both GenBank and EMBL feature tables
+const FEATURE_KEY_COLUMN: usize = 5;
+const FEATURE_VALUE_COLUMN: usize = 21;
+const GENBANK_VALUE_COLUMN: usize = 12;
+const EMBL_VALUE_COLUMN: usize = 5;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Feature {
+    pub kind: String,
+    pub location: String,
+    // Half-open 0-based ranges on the forward strand
+    pub ranges: Vec<(usize, usize)>,
+    pub strand: Strand,
+    pub qualifiers: Vec<(String, String)>,
+}
+
+impl Feature {
+    pub fn qualifier(&self, name: &str) -> Option<&str> {
+        self.qualifiers
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Section {
+    Header,
+    Features,
+    Sequence,
+}
+
+impl SequenceReader {
+    // Collects lines up to the "//" terminator and parses them as one record
+    pub(super) fn read_flat(&mut self, header: String) -> Result<SequenceRecord> {
+        let mut lines = vec![header];
+
+        loop {
+            match self.read_line()? {
+                Some(line) if line.starts_with("//") => break,
+                Some(line) => lines.push(line),
+                None => return Err(self.parse_error("record terminator '//' is missing")),
+            }
+        }
+
+        let parsed = match self.format {
+            Some(SequenceFormat::Embl) => parse_embl(&lines),
+            _ => parse_genbank(&lines),
+        };
+
+        parsed.map_err(|message| self.parse_error(&message))
+    }
+}
+
+fn value_from(line: &str, column: usize) -> &str {
+    line.get(column..).unwrap_or("").trim()
+}
+
+fn append(target: &mut Option<String>, value: &str) {
+    match target {
+        Some(text) => {
+            text.push(' ');
+            text.push_str(value);
+        }
+        None => *target = Some(value.to_string()),
+    }
+}
+
+fn residues(line: &str) -> impl Iterator<Item = u8> + '_ {
+    line.bytes()
+        .filter(|byte| byte.is_ascii_alphabetic() || *byte == b'*' || *byte == b'-')
+        .map(|byte| byte.to_ascii_uppercase())
+}
+
+fn parse_genbank(lines: &[String]) -> std::result::Result<SequenceRecord, String> {
+    let mut name = None;
+    let mut accession = None;
+    let mut description: Option<String> = None;
+    let mut sequence = vec![];
+    let mut features = FeatureTable::default();
+    let mut section = Section::Header;
+    let mut keyword = String::new();
+
+    for line in lines.iter() {
+        // Keywords are flush left, continuation lines are indented
+        if !line.starts_with(' ') && !line.is_empty() {
+            keyword = line
+                .get(..GENBANK_VALUE_COLUMN)
+                .unwrap_or(line)
+                .trim()
+                .to_string();
+            section = match keyword.as_str() {
+                "FEATURES" => Section::Features,
+                "ORIGIN" => Section::Sequence,
+                _ => Section::Header,
+            };
+
+            let value = value_from(line, GENBANK_VALUE_COLUMN);
+            let first = value.split_whitespace().next().map(String::from);
+
+            match keyword.as_str() {
+                "LOCUS" => name = first,
+                "VERSION" => accession = first.or(accession),
+                "ACCESSION" if accession.is_none() => accession = first,
+                "DEFINITION" => append(&mut description, value),
+                _ => {}
+            }
+
+            continue;
+        }
+
+        match section {
+            Section::Features => features.push_line(line)?,
+            Section::Sequence => sequence.extend(residues(line)),
+            Section::Header if keyword == "DEFINITION" => {
+                append(&mut description, value_from(line, GENBANK_VALUE_COLUMN))
+            }
+            Section::Header => {}
+        }
+    }
+
+    let id = match accession.or(name) {
+        Some(id) => id,
+        None => return Err("LOCUS name is missing".to_string()),
+    };
+
+    Ok(SequenceRecord {
+        id,
+        description: description.map(|text| text.trim_end_matches('.').to_string()),
+        sequence,
+        quality: None,
+        features: features.finish()?,
+    })
+}
+
+fn parse_embl(lines: &[String]) -> std::result::Result<SequenceRecord, String> {
+    let mut id = None;
+    let mut description: Option<String> = None;
+    let mut sequence = vec![];
+    let mut features = FeatureTable::default();
+    let mut section = Section::Header;
+
+    for line in lines.iter() {
+        let code = line.get(..2).unwrap_or(line);
+        let value = value_from(line, EMBL_VALUE_COLUMN);
+
+        match code {
+            "ID" => {
+                id = value
+                    .split(';')
+                    .next()
+                    .map(|token| token.trim().to_string())
+                    .filter(|token| !token.is_empty())
+            }
+            "DE" => append(&mut description, value),
+            "FT" => features.push_line(line)?,
+            "SQ" => section = Section::Sequence,
+            _ if section == Section::Sequence => {
+                sequence.extend(residues(line));
+            }
+            _ => {}
+        }
+    }
+
+    let id = match id {
+        Some(id) => id,
+        None => return Err("ID line is missing".to_string()),
+    };
+
+    Ok(SequenceRecord {
+        id,
+        description: description.map(|text| text.trim_end_matches('.').to_string()),
+        sequence,
+        quality: None,
+        features: features.finish()?,
+    })
+}
+
+#[derive(Default)]
+struct FeatureTable {
+    features: Vec<Feature>,
+    // Ranges and strand are filled in once the location is complete
+    current: Option<Feature>,
+}
+
+impl FeatureTable {
+    fn push_line(&mut self, line: &str) -> std::result::Result<(), String> {
+        let key = line
+            .get(FEATURE_KEY_COLUMN..FEATURE_VALUE_COLUMN.min(line.len()))
+            .unwrap_or("")
+            .trim();
+        let value = value_from(line, FEATURE_VALUE_COLUMN);
+
+        // The EMBL header of the feature table
+        if key == "Key" {
+            return Ok(());
+        }
+
+        if !key.is_empty() {
+            self.flush()?;
+            self.current = Some(Feature {
+                kind: key.to_string(),
+                location: value.to_string(),
+                ranges: vec![],
+                strand: Strand::Forward,
+                qualifiers: vec![],
+            });
+            return Ok(());
+        }
+
+        let feature = match self.current.as_mut() {
+            Some(feature) => feature,
+            None => return Ok(()),
+        };
+
+        if let Some(qualifier) = value.strip_prefix('/') {
+            let (name, text) = qualifier.split_once('=').unwrap_or((qualifier, ""));
+            feature
+                .qualifiers
+                .push((name.to_string(), text.to_string()));
+        } else if let Some((name, text)) = feature.qualifiers.last_mut() {
+            // Protein translations are wrapped without separating spaces
+            if name != "translation" {
+                text.push(' ');
+            }
+            text.push_str(value);
+        } else {
+            feature.location.push_str(value);
+        }
+
+        Ok(())
+    }
+
+    fn flush(&mut self) -> std::result::Result<(), String> {
+        if let Some(mut feature) = self.current.take() {
+            (feature.ranges, feature.strand) = match parse_location(&feature.location) {
+                Some(parsed) => parsed,
+                None => {
+                    return Err(format!(
+                        "malformed location {} of {}",
+                        feature.location, feature.kind
+                    ))
+                }
+            };
+
+            for (_, text) in feature.qualifiers.iter_mut() {
+                *text = text.trim_matches('"').to_string();
+            }
+
+            self.features.push(feature);
+        }
+
+        Ok(())
+    }
+
+    fn finish(mut self) -> std::result::Result<Vec<Feature>, String> {
+        self.flush()?;
+        Ok(self.features)
+    }
+}
+
+// Supports spans, single bases, partial ends and complement/join/order;
+// between-base sites and references to other entries are left out
+fn parse_location(location: &str) -> Option<(Vec<(usize, usize)>, Strand)> {
+    let strand = match location.contains("complement(") {
+        true => Strand::Reverse,
+        false => Strand::Forward,
+    };
+
+    let mut ranges = vec![];
+    let plain = ["complement(", "join(", "order(", ")"]
+        .iter()
+        .fold(location.to_string(), |text, token| text.replace(token, ""));
+
+    for part in plain.split(',').map(str::trim) {
+        if part.is_empty() || part.contains(':') || part.contains('^') {
+            continue;
+        }
+
+        let part: String = part.chars().filter(|c| *c != '<' && *c != '>').collect();
+        let (left, right) = part.split_once("..").unwrap_or((&part, &part));
+        let left = left.trim().parse::<usize>().ok()?;
+        let right = right.trim().parse::<usize>().ok()?;
+
+        if left == 0 || right < left {
+            return None;
+        }
+
+        ranges.push((left - 1, right));
+    }
+
+    Some((ranges, strand))
+}
@@ -0,0 +1,268 @@
+use crate::enums::Index;
+use crate::reader::flat::Feature;
+use crate::{BioData, Error, Result};
+use flate2::read::MultiGzDecoder;
+use ndarray::Array1;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+
+pub mod flat;
+#[cfg(test)]
+mod test;
+pub mod twobit;
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+const FASTA_WIDTH: usize = 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SequenceFormat {
+    Fasta,
+    Fastq,
+    GenBank,
+    Embl,
+}
+
+#[derive(Debug, Clone)]
+pub struct SequenceRecord {
+    pub id: String,
+    pub description: Option<String>,
+    pub sequence: Vec<u8>,
+    pub quality: Option<Vec<u8>>,
+    // Feature table of GenBank and EMBL records
+    pub features: Vec<Feature>,
+}
+
+impl SequenceRecord {
+    // Whole header line without the leading marker
+    pub fn head(&self) -> String {
+        match &self.description {
+            Some(description) => format!("{} {}", self.id, description),
+            None => self.id.clone(),
+        }
+    }
+
+    // Residues with frequencies and the indices of skipped (unparsable) runs,
+    // see BioData::from_u8_vec_with_freqs_and_indices
+    pub fn to_bio_data<T: BioData>(&self) -> Result<(Vec<T>, Array1<f64>, Vec<Index>)> {
+        T::from_u8_vec_with_freqs_and_indices(&self.sequence)
+    }
+
+    // FASTA entry with the sequence wrapped at 60 residues
+    pub fn to_fasta(&self) -> String {
+        let mut result = format!(">{}\n", self.head());
+
+        for line in self.sequence.chunks(FASTA_WIDTH) {
+            result.push_str(&String::from_utf8_lossy(line));
+            result.push('\n');
+        }
+
+        result
+    }
+
+    // Ranges of the features of given kinds (e.g. repeat_region or CDS),
+    // usable as known-repeat masks
+    pub fn feature_ranges(&self, kinds: &[String]) -> Vec<(usize, usize)> {
+        self.features
+            .iter()
+            .filter(|feature| kinds.contains(&feature.kind))
+            .flat_map(|feature| feature.ranges.iter().cloned())
+            .collect()
+    }
+}
+
+// Reads FASTA, FASTQ, GenBank or EMBL record by record, the format is taken
+// from the first record and gzip input is detected by its magic bytes
+pub struct SequenceReader {
+    reader: Box<dyn BufRead>,
+    format: Option<SequenceFormat>,
+    header: Option<String>,
+    line: usize,
+    finished: bool,
+}
+
+impl SequenceReader {
+    pub fn from_path(path: &Path) -> Result<SequenceReader> {
+        match File::open(path) {
+            Ok(file) => SequenceReader::new(file),
+            Err(err) => {
+                error!("Unable to open {}: {}", path.display(), err);
+                Err(Error::IOError)
+            }
+        }
+    }
+
+    pub fn new<R: Read + 'static>(reader: R) -> Result<SequenceReader> {
+        let mut reader = BufReader::new(reader);
+
+        let compressed = match reader.fill_buf() {
+            Ok(buffer) => buffer.starts_with(&GZIP_MAGIC),
+            Err(_) => return Err(Error::IOError),
+        };
+
+        let reader: Box<dyn BufRead> = match compressed {
+            true => Box::new(BufReader::new(MultiGzDecoder::new(reader))),
+            false => Box::new(reader),
+        };
+
+        Ok(SequenceReader {
+            reader,
+            format: None,
+            header: None,
+            line: 0,
+            finished: false,
+        })
+    }
+
+    pub fn format(&self) -> Option<SequenceFormat> {
+        self.format
+    }
+
+    fn read_line(&mut self) -> Result<Option<String>> {
+        let mut buffer = String::new();
+
+        match self.reader.read_line(&mut buffer) {
+            Ok(0) => Ok(None),
+            Ok(_) => {
+                self.line += 1;
+                Ok(Some(buffer.trim_end_matches(['\n', '\r']).to_string()))
+            }
+            Err(err) => {
+                error!("Unable to read line {}: {}", self.line + 1, err);
+                Err(Error::IOError)
+            }
+        }
+    }
+
+    fn parse_error(&self, message: &str) -> Error {
+        error!("Malformed sequence file at line {}: {}", self.line, message);
+        Error::ParseError
+    }
+
+    fn read_header(&mut self) -> Result<Option<String>> {
+        if let Some(header) = self.header.take() {
+            return Ok(Some(header));
+        }
+
+        while let Some(line) = self.read_line()? {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let format = if line.starts_with('>') {
+                SequenceFormat::Fasta
+            } else if line.starts_with('@') {
+                SequenceFormat::Fastq
+            } else if line.starts_with("LOCUS") {
+                SequenceFormat::GenBank
+            } else if line.starts_with("ID ") {
+                SequenceFormat::Embl
+            } else {
+                return Err(self.parse_error("record header is expected"));
+            };
+
+            match self.format {
+                None => self.format = Some(format),
+                Some(current) if current != format => {
+                    return Err(self.parse_error("records of different formats are mixed"))
+                }
+                _ => {}
+            }
+
+            return match format {
+                SequenceFormat::Fasta | SequenceFormat::Fastq => Ok(Some(line[1..].to_string())),
+                _ => Ok(Some(line)),
+            };
+        }
+
+        Ok(None)
+    }
+
+    fn read_fasta(&mut self) -> Result<(Vec<u8>, Option<Vec<u8>>)> {
+        let mut sequence = vec![];
+
+        while let Some(line) = self.read_line()? {
+            if let Some(header) = line.strip_prefix('>') {
+                self.header = Some(header.to_string());
+                break;
+            }
+
+            sequence.extend(line.bytes().filter(|byte| !byte.is_ascii_whitespace()));
+        }
+
+        Ok((sequence, None))
+    }
+
+    fn read_fastq(&mut self) -> Result<(Vec<u8>, Option<Vec<u8>>)> {
+        let sequence = match self.read_line()? {
+            Some(line) => line.trim().as_bytes().to_vec(),
+            None => return Err(self.parse_error("sequence line is missing")),
+        };
+
+        match self.read_line()? {
+            Some(line) if line.starts_with('+') => {}
+            _ => return Err(self.parse_error("'+' separator line is expected")),
+        }
+
+        let quality = match self.read_line()? {
+            Some(line) => line.trim().as_bytes().to_vec(),
+            None => return Err(self.parse_error("quality line is missing")),
+        };
+
+        if quality.len() != sequence.len() {
+            return Err(self.parse_error("quality and sequence lengths differ"));
+        }
+
+        Ok((sequence, Some(quality)))
+    }
+
+    fn read_record(&mut self) -> Result<Option<SequenceRecord>> {
+        let header = match self.read_header()? {
+            Some(header) => header,
+            None => return Ok(None),
+        };
+
+        let (sequence, quality) = match self.format {
+            Some(SequenceFormat::GenBank) | Some(SequenceFormat::Embl) => {
+                return self.read_flat(header).map(Some)
+            }
+            Some(SequenceFormat::Fastq) => self.read_fastq()?,
+            _ => self.read_fasta()?,
+        };
+
+        let (id, description) = match header.split_once(char::is_whitespace) {
+            Some((id, description)) => (id.to_string(), Some(description.trim().to_string())),
+            None => (header.trim().to_string(), None),
+        };
+
+        Ok(Some(SequenceRecord {
+            id,
+            description,
+            sequence,
+            quality,
+            features: vec![],
+        }))
+    }
+}
+
+impl Iterator for SequenceReader {
+    type Item = Result<SequenceRecord>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        match self.read_record() {
+            Ok(Some(record)) => Some(Ok(record)),
+            Ok(None) => {
+                self.finished = true;
+                None
+            }
+            Err(err) => {
+                self.finished = true;
+                Some(Err(err))
+            }
+        }
+    }
+}
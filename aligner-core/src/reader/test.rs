@@ -0,0 +1,222 @@
+use crate::enums::{Strand, DNA};
+use crate::reader::twobit::{range_to_bio_data, TwoBitReader};
+use crate::reader::{SequenceFormat, SequenceReader};
+use crate::Error;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::io::Write;
+
+const FASTA: &str = ">chr1 first chromosome\nATGC\r\nNNAT\n\n>chr2\nGGCC\n";
+const FASTQ: &str = "@read1 sample\nATGC\n+\nIIII\n@read2\nGG\n+read2\nI#\n";
+const GENBANK: &str = "\
+LOCUS       SCU49845                  24 bp    DNA     linear   PLN 21-JUN-1999
+DEFINITION  Saccharomyces cerevisiae TCP1-beta gene, partial cds, and Axl2p
+            (AXL2) genes.
+ACCESSION   U49845
+VERSION     U49845.1
+FEATURES             Location/Qualifiers
+     source          1..24
+                     /organism=\"Saccharomyces cerevisiae\"
+     CDS             join(<1..6,
+                     10..12)
+                     /product=\"TCP1-beta\"
+     repeat_region   complement(13..20)
+                     /rpt_family=\"Ty1\"
+ORIGIN
+        1 gatcctccat atacaacggt atct
+//
+";
+const EMBL: &str = "\
+ID   X56734; SV 1; linear; mRNA; STD; PLN; 12 BP.
+XX
+DE   Trifolium repens mRNA for
+DE   beta-glucosidase
+XX
+FH   Key             Location/Qualifiers
+FT   repeat_region   3..8
+FT                   /note=\"known
+FT                   repeat\"
+XX
+SQ   Sequence 12 BP; 4 A; 3 C; 0 G; 5 T; 0 other;
+     aaacaaacca at                                                      12
+//
+";
+
+#[test]
+fn fasta_reader_test() {
+    let records: Vec<_> = SequenceReader::new(FASTA.as_bytes())
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].id, "chr1");
+    assert_eq!(records[0].head(), "chr1 first chromosome");
+    assert_eq!(records[0].sequence, b"ATGCNNAT");
+    assert_eq!(records[1].head(), "chr2");
+    assert!(records[1].quality.is_none());
+    assert_eq!(records[1].to_fasta(), ">chr2\nGGCC\n");
+
+    let (sequence, _, indices) = records[0].to_bio_data::<DNA>().unwrap();
+    assert_eq!(sequence.len(), 6);
+    assert_eq!(indices.len(), 1);
+    assert_eq!(indices[0].offset, 2);
+}
+
+#[test]
+fn gzipped_fastq_reader_test() {
+    let mut encoder = GzEncoder::new(vec![], Compression::default());
+    encoder.write_all(FASTQ.as_bytes()).unwrap();
+    let compressed = encoder.finish().unwrap();
+
+    let mut reader = SequenceReader::new(std::io::Cursor::new(compressed)).unwrap();
+    let first = reader.next().unwrap().unwrap();
+
+    assert_eq!(reader.format(), Some(SequenceFormat::Fastq));
+    assert_eq!(first.sequence, b"ATGC");
+    assert_eq!(first.quality.unwrap(), b"IIII");
+    assert_eq!(reader.next().unwrap().unwrap().id, "read2");
+    assert!(reader.next().is_none());
+}
+
+#[test]
+fn malformed_input_test() {
+    let broken = "@read1\nATGC\n+\nIII\n";
+    let mut reader = SequenceReader::new(broken.as_bytes()).unwrap();
+    assert!(matches!(reader.next(), Some(Err(Error::ParseError))));
+    assert!(reader.next().is_none());
+
+    let mut reader = SequenceReader::new("ATGC\n".as_bytes()).unwrap();
+    assert!(matches!(reader.next(), Some(Err(Error::ParseError))));
+
+    assert!(matches!(
+        SequenceReader::from_path(std::path::Path::new("/nonexistent.fa")),
+        Err(Error::IOError)
+    ));
+}
+
+#[test]
+fn genbank_reader_test() {
+    let mut reader = SequenceReader::new(GENBANK.as_bytes()).unwrap();
+    let record = reader.next().unwrap().unwrap();
+
+    assert_eq!(reader.format(), Some(SequenceFormat::GenBank));
+    assert!(reader.next().is_none());
+    assert_eq!(record.id, "U49845.1");
+    assert_eq!(
+        record.description.as_deref(),
+        Some("Saccharomyces cerevisiae TCP1-beta gene, partial cds, and Axl2p (AXL2) genes")
+    );
+    assert_eq!(record.sequence, b"GATCCTCCATATACAACGGTATCT");
+    assert_eq!(record.features.len(), 3);
+
+    let cds = &record.features[1];
+    assert_eq!(cds.ranges, vec![(0, 6), (9, 12)]);
+    assert_eq!(cds.qualifier("product"), Some("TCP1-beta"));
+    assert_eq!(record.features[2].strand, Strand::Reverse);
+    assert_eq!(record.features[2].qualifier("rpt_family"), Some("Ty1"));
+
+    let kinds = vec!["repeat_region".to_string(), "CDS".to_string()];
+    assert_eq!(
+        record.feature_ranges(&kinds),
+        vec![(0, 6), (9, 12), (12, 20)]
+    );
+}
+
+#[test]
+fn embl_reader_test() {
+    let mut reader = SequenceReader::new(EMBL.as_bytes()).unwrap();
+    let record = reader.next().unwrap().unwrap();
+
+    assert_eq!(reader.format(), Some(SequenceFormat::Embl));
+    assert_eq!(
+        record.head(),
+        "X56734 Trifolium repens mRNA for beta-glucosidase"
+    );
+    assert_eq!(record.sequence, b"AAACAAACCAAT");
+    assert_eq!(record.features[0].ranges, vec![(2, 8)]);
+    assert_eq!(record.features[0].qualifier("note"), Some("known repeat"));
+
+    let truncated = &EMBL[..EMBL.len() - 3];
+    let mut reader = SequenceReader::new(truncated.as_bytes()).unwrap();
+    assert!(matches!(reader.next(), Some(Err(Error::ParseError))));
+}
+
+// Little-endian .2bit with a single sequence, blocks are (start, size) pairs
+fn get_two_bit(
+    name: &str,
+    bases: &[u8],
+    n_blocks: &[(u32, u32)],
+    mask_blocks: &[(u32, u32)],
+) -> Vec<u8> {
+    let mut result = vec![];
+    for value in [0x1A41_2743u32, 0, 1, 0] {
+        result.extend(value.to_le_bytes());
+    }
+
+    result.push(name.len() as u8);
+    result.extend(name.as_bytes());
+    result.extend((result.len() as u32 + 4).to_le_bytes());
+
+    result.extend((bases.len() as u32).to_le_bytes());
+    for blocks in [n_blocks, mask_blocks] {
+        result.extend((blocks.len() as u32).to_le_bytes());
+        blocks
+            .iter()
+            .for_each(|(start, _)| result.extend(start.to_le_bytes()));
+        blocks
+            .iter()
+            .for_each(|(_, size)| result.extend(size.to_le_bytes()));
+    }
+    result.extend(0u32.to_le_bytes());
+
+    for chunk in bases.chunks(4) {
+        let mut byte = 0u8;
+        for i in 0..4 {
+            let code = match chunk.get(i) {
+                Some(b'C') => 1,
+                Some(b'A') => 2,
+                Some(b'G') => 3,
+                _ => 0,
+            };
+            byte |= code << (6 - 2 * i);
+        }
+        result.push(byte);
+    }
+
+    result
+}
+
+#[test]
+fn two_bit_reader_test() {
+    let data = get_two_bit("chr1", b"ACGTTTTTACGTACGTA", &[(4, 4)], &[(8, 4)]);
+    let mut reader = TwoBitReader::new(std::io::Cursor::new(data)).unwrap();
+
+    assert_eq!(reader.names(), vec!["chr1".to_string()]);
+    assert_eq!(reader.length("chr1").unwrap(), 17);
+    assert_eq!(
+        reader.read_sequence("chr1").unwrap().sequence,
+        b"ACGTNNNNacgtACGTA"
+    );
+    assert_eq!(reader.read_range("chr1", 3, 10).unwrap(), b"TNNNNac");
+    assert!(matches!(
+        reader.read_range("chr1", 3, 18),
+        Err(Error::ValidationError)
+    ));
+    assert!(matches!(reader.length("chr2"), Err(Error::ValidationError)));
+
+    // Parsed positions map back to the genome through the indices
+    let raw = reader.read_range("chr1", 2, 14).unwrap();
+    let (sequence, _, indices) = range_to_bio_data::<DNA>(&raw, 2).unwrap();
+    let genome_coord = |target: usize| {
+        indices
+            .iter()
+            .find(|index| target >= index.coord)
+            .map_or(target, |index| target + index.offset)
+    };
+
+    assert_eq!(sequence.len(), 4);
+    assert_eq!(genome_coord(0), 2);
+    assert_eq!(genome_coord(1), 3);
+    assert_eq!(genome_coord(2), 12);
+}
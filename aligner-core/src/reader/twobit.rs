@@ -0,0 +1,270 @@
+use crate::enums::Index;
+use crate::reader::SequenceRecord;
+use crate::{BioData, Error, Result};
+use ndarray::Array1;
+use std::fs::File;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+
+const TWO_BIT_SIGNATURE: u32 = 0x1A41_2743;
+// Two-bit codes in the order used by UCSC
+const BASES: [u8; 4] = [b'T', b'C', b'A', b'G'];
+
+// Layout of a sequence record, blocks are (start, size) pairs
+struct TwoBitRecord {
+    length: usize,
+    n_blocks: Vec<(usize, usize)>,
+    mask_blocks: Vec<(usize, usize)>,
+    packed_offset: u64,
+}
+
+// Random access to UCSC .2bit genomes: only the index is read up front and
+// every range is decoded straight from its packed bases. N-blocks come back
+// as 'N' and soft-masked blocks as lowercase bases
+pub struct TwoBitReader<R: Read + Seek> {
+    reader: R,
+    big_endian: bool,
+    long_offsets: bool,
+    sequences: Vec<(String, u64)>,
+}
+
+impl TwoBitReader<BufReader<File>> {
+    pub fn from_path(path: &Path) -> Result<TwoBitReader<BufReader<File>>> {
+        match File::open(path) {
+            Ok(file) => TwoBitReader::new(BufReader::new(file)),
+            Err(err) => {
+                error!("Unable to open {}: {}", path.display(), err);
+                Err(Error::IOError)
+            }
+        }
+    }
+}
+
+impl<R: Read + Seek> TwoBitReader<R> {
+    pub fn new(reader: R) -> Result<TwoBitReader<R>> {
+        let mut result = TwoBitReader {
+            reader,
+            big_endian: false,
+            long_offsets: false,
+            sequences: vec![],
+        };
+
+        let signature = result.read_u32()?;
+        if signature == TWO_BIT_SIGNATURE.swap_bytes() {
+            result.big_endian = true;
+        } else if signature != TWO_BIT_SIGNATURE {
+            error!("Not a 2bit file, signature {:#x}", signature);
+            return Err(Error::ParseError);
+        }
+
+        result.long_offsets = match result.read_u32()? {
+            0 => false,
+            1 => true,
+            version => {
+                error!("Unsupported 2bit version {}", version);
+                return Err(Error::ParseError);
+            }
+        };
+
+        let count = result.read_u32()?;
+        result.read_u32()?;
+
+        for _ in 0..count {
+            let mut size = [0u8];
+            result.read_exact(&mut size)?;
+
+            let mut name = vec![0u8; size[0] as usize];
+            result.read_exact(&mut name)?;
+
+            let offset = match result.long_offsets {
+                true => result.read_u64()?,
+                false => result.read_u32()? as u64,
+            };
+
+            result
+                .sequences
+                .push((String::from_utf8_lossy(&name).to_string(), offset));
+        }
+
+        Ok(result)
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        self.sequences
+            .iter()
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+
+    pub fn length(&mut self, name: &str) -> Result<usize> {
+        Ok(self.read_record(name)?.length)
+    }
+
+    // Bases of [start, end) of the named sequence
+    pub fn read_range(&mut self, name: &str, start: usize, end: usize) -> Result<Vec<u8>> {
+        let record = self.read_record(name)?;
+
+        if start > end || end > record.length {
+            error!(
+                "Range {}..{} is out of {} with length {}",
+                start, end, name, record.length
+            );
+            return Err(Error::ValidationError);
+        }
+
+        let first = start / 4;
+        let mut packed = vec![0u8; end.div_ceil(4) - first];
+        self.seek(record.packed_offset + first as u64)?;
+        self.read_exact(&mut packed)?;
+
+        let mut result: Vec<u8> = (start..end)
+            .map(|i| BASES[((packed[i / 4 - first] >> (6 - 2 * (i % 4))) & 3) as usize])
+            .collect();
+
+        for (block_start, size) in record.n_blocks.iter() {
+            for elem in result[overlap(*block_start, *size, start, end)].iter_mut() {
+                *elem = b'N';
+            }
+        }
+
+        for (block_start, size) in record.mask_blocks.iter() {
+            result[overlap(*block_start, *size, start, end)].make_ascii_lowercase();
+        }
+
+        Ok(result)
+    }
+
+    pub fn read_sequence(&mut self, name: &str) -> Result<SequenceRecord> {
+        let length = self.length(name)?;
+
+        Ok(SequenceRecord {
+            id: name.to_string(),
+            description: None,
+            sequence: self.read_range(name, 0, length)?,
+            quality: None,
+            features: vec![],
+        })
+    }
+
+    fn read_record(&mut self, name: &str) -> Result<TwoBitRecord> {
+        let offset = match self.sequences.iter().find(|(elem, _)| elem == name) {
+            Some((_, offset)) => *offset,
+            None => {
+                error!("Sequence {} is not found in the 2bit file", name);
+                return Err(Error::ValidationError);
+            }
+        };
+
+        self.seek(offset)?;
+
+        let length = self.read_u32()? as usize;
+        let n_blocks = self.read_blocks()?;
+        let mask_blocks = self.read_blocks()?;
+        self.read_u32()?;
+
+        let packed_offset = match self.reader.stream_position() {
+            Ok(position) => position,
+            Err(_) => return Err(Error::IOError),
+        };
+
+        Ok(TwoBitRecord {
+            length,
+            n_blocks,
+            mask_blocks,
+            packed_offset,
+        })
+    }
+
+    fn read_blocks(&mut self) -> Result<Vec<(usize, usize)>> {
+        let count = self.read_u32()? as usize;
+
+        let mut starts = vec![];
+        for _ in 0..count {
+            starts.push(self.read_u32()? as usize);
+        }
+
+        let mut result = vec![];
+        for start in starts.into_iter() {
+            result.push((start, self.read_u32()? as usize));
+        }
+
+        Ok(result)
+    }
+
+    fn seek(&mut self, offset: u64) -> Result<()> {
+        match self.reader.seek(SeekFrom::Start(offset)) {
+            Ok(_) => Ok(()),
+            Err(err) => {
+                error!("Unable to seek to {} in the 2bit file: {}", offset, err);
+                Err(Error::IOError)
+            }
+        }
+    }
+
+    fn read_exact(&mut self, buffer: &mut [u8]) -> Result<()> {
+        match self.reader.read_exact(buffer) {
+            Ok(_) => Ok(()),
+            Err(err) => {
+                error!("Truncated 2bit file: {}", err);
+                Err(Error::IOError)
+            }
+        }
+    }
+
+    fn read_u32(&mut self) -> Result<u32> {
+        let mut buffer = [0u8; 4];
+        self.read_exact(&mut buffer)?;
+
+        Ok(match self.big_endian {
+            true => u32::from_be_bytes(buffer),
+            false => u32::from_le_bytes(buffer),
+        })
+    }
+
+    fn read_u64(&mut self) -> Result<u64> {
+        let mut buffer = [0u8; 8];
+        self.read_exact(&mut buffer)?;
+
+        Ok(match self.big_endian {
+            true => u64::from_be_bytes(buffer),
+            false => u64::from_le_bytes(buffer),
+        })
+    }
+}
+
+// Part of the block inside [start, end) relative to start
+fn overlap(block_start: usize, size: usize, start: usize, end: usize) -> std::ops::Range<usize> {
+    let left = block_start.max(start).min(end);
+    let right = (block_start + size).min(end).max(left);
+
+    left - start..right - start
+}
+
+// Parses bases of a genome range which begins at start. N-blocks and
+// soft-masked (lowercase) bases are skipped, and the returned indices lead
+// back to genome coordinates, see BioData::from_u8_vec_with_freqs_and_indices
+pub fn range_to_bio_data<T: BioData>(
+    raw: &[u8],
+    start: usize,
+) -> Result<(Vec<T>, Array1<f64>, Vec<Index>)> {
+    let (sequence, frequencies, mut indices) = T::from_u8_vec_with_freqs_and_indices(raw)?;
+
+    if start == 0 {
+        return Ok((sequence, frequencies, indices));
+    }
+
+    for index in indices.iter_mut() {
+        index.offset += start;
+    }
+
+    match indices.last_mut() {
+        Some(index) if index.coord == 0 => index.local_offset += start,
+        _ => indices.push(Index {
+            coord: 0,
+            offset: start,
+            local_offset: start,
+        }),
+    }
+
+    Ok((sequence, frequencies, indices))
+}
@@ -0,0 +1,128 @@
+use crate::alignment::cigar::EditOp;
+use crate::alignment::Alignment;
+use crate::enums::{BioData, DNA};
+use crate::{Error, Result};
+
+#[cfg(test)]
+mod test;
+
+const SAM_VERSION: &str = "1.6";
+const MAX_MAPQ: f64 = 60f64;
+// MAPQ value reserved by SAM for "not available"
+pub const MAPQ_UNAVAILABLE: u8 = 255;
+
+#[derive(Debug, Clone)]
+pub struct SamRecord {
+    pub query_name: String,
+    pub flag: u16,
+    pub reference_name: String,
+    pub pos: usize,
+    pub mapq: u8,
+    pub cigar: String,
+    pub sequence: String,
+    pub score: i64,
+    pub edit_distance: usize,
+}
+
+impl SamRecord {
+    // The target of the alignment is the reference; query parts outside of the
+    // alignment are soft-clipped so that CIGAR covers the whole query
+    pub fn from_alignment(
+        query_name: &str,
+        query: &[DNA],
+        reference_name: &str,
+        alignment: &Alignment<DNA>,
+        mapq: u8,
+    ) -> Result<SamRecord> {
+        let cigar = alignment.get_cigar(false);
+        let (query_start, target_start) = (alignment.coords.0 .0, alignment.coords.1 .0);
+
+        if query_start == 0 || target_start == 0 {
+            return Err(Error::ValidationError);
+        }
+
+        let head = query_start - 1;
+        let tail = match query.len().checked_sub(head + cigar.query_length()) {
+            Some(tail) => tail,
+            None => return Err(Error::ValidationError),
+        };
+
+        let mut cigar_string = String::new();
+        if head > 0 {
+            cigar_string.push_str(&format!("{}S", head));
+        }
+        cigar_string.push_str(&cigar.to_string());
+        if tail > 0 {
+            cigar_string.push_str(&format!("{}S", tail));
+        }
+
+        let edit_distance = alignment
+            .get_edit_ops(true)
+            .iter()
+            .filter(|op| **op != EditOp::Equal)
+            .count();
+
+        Ok(SamRecord {
+            query_name: query_name.to_string(),
+            flag: 0,
+            reference_name: reference_name.to_string(),
+            pos: target_start,
+            mapq,
+            cigar: cigar_string,
+            sequence: DNA::vec_to_str(query)?,
+            score: alignment.f.round() as i64,
+            edit_distance,
+        })
+    }
+
+    pub fn to_line(&self) -> String {
+        format!(
+            "{}\t{}\t{}\t{}\t{}\t{}\t*\t0\t0\t{}\t*\tAS:i:{}\tNM:i:{}",
+            self.query_name,
+            self.flag,
+            self.reference_name,
+            self.pos,
+            self.mapq,
+            self.cigar,
+            self.sequence,
+            self.score,
+            self.edit_distance
+        )
+    }
+}
+
+// Phred-scaled probability that the alignment is a chance hit, capped like
+// the usual aligners do
+pub fn get_mapq(p_value: f64) -> u8 {
+    if !p_value.is_finite() || p_value < 0f64 {
+        return MAPQ_UNAVAILABLE;
+    }
+
+    (-10f64 * p_value.max(f64::MIN_POSITIVE).log10())
+        .clamp(0f64, MAX_MAPQ)
+        .round() as u8
+}
+
+// References are (name, length) pairs in the order of the source FASTA
+pub fn get_sam_header(references: &[(String, usize)], program: &str) -> String {
+    let mut header = format!("@HD\tVN:{}\tSO:unsorted\n", SAM_VERSION);
+
+    for (name, length) in references.iter() {
+        header.push_str(&format!("@SQ\tSN:{}\tLN:{}\n", name, length));
+    }
+
+    header.push_str(&format!("@PG\tID:{}\tPN:{}\n", program, program));
+
+    header
+}
+
+pub fn write_sam(references: &[(String, usize)], records: &[SamRecord], program: &str) -> String {
+    let mut result = get_sam_header(references, program);
+
+    for record in records.iter() {
+        result.push_str(&record.to_line());
+        result.push('\n');
+    }
+
+    result
+}
@@ -0,0 +1,50 @@
+use crate::alignment::Alignment;
+use crate::enums::{BioData, DNA};
+use crate::sam::{get_mapq, write_sam, SamRecord, MAPQ_UNAVAILABLE};
+
+#[test]
+fn sam_record_test() {
+    let query = DNA::str_to_vec("GGATGCAATCC").unwrap();
+    let alignment = Alignment::new(
+        DNA::str_to_vec("ATG_CAAT").unwrap(),
+        DNA::str_to_vec("ATGGC_TT").unwrap(),
+        ((3, 9), (101, 107)),
+        7f64,
+    )
+    .unwrap();
+
+    let record = SamRecord::from_alignment("read", &query, "chr1", &alignment, 37).unwrap();
+
+    assert_eq!(
+        record.to_line(),
+        "read\t0\tchr1\t101\t37\t2S3M1D1M1I2M2S\t*\t0\t0\tGGATGCAATCC\t*\tAS:i:7\tNM:i:3"
+    );
+
+    let sam = write_sam(&[(String::from("chr1"), 1000)], &[record], "aligner-cli");
+    let lines: Vec<&str> = sam.lines().collect();
+
+    assert_eq!(lines[1], "@SQ\tSN:chr1\tLN:1000");
+    assert_eq!(lines.len(), 4);
+}
+
+#[test]
+fn sam_record_validation_test() {
+    let query = DNA::str_to_vec("ATG").unwrap();
+    let alignment = Alignment::new(
+        DNA::str_to_vec("ATGC").unwrap(),
+        DNA::str_to_vec("ATGC").unwrap(),
+        ((1, 4), (1, 4)),
+        4f64,
+    )
+    .unwrap();
+
+    assert!(SamRecord::from_alignment("read", &query, "chr1", &alignment, 0).is_err());
+}
+
+#[test]
+fn mapq_test() {
+    assert_eq!(get_mapq(1f64), 0);
+    assert_eq!(get_mapq(0.001), 30);
+    assert_eq!(get_mapq(0f64), 60);
+    assert_eq!(get_mapq(f64::NAN), MAPQ_UNAVAILABLE);
+}
@@ -0,0 +1,83 @@
+use crate::{Error, Result};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+#[cfg(test)]
+mod test;
+
+// Bumped on every incompatible change of the serialized types
+pub const FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Versioned<T> {
+    pub format_version: u32,
+    pub data: T,
+}
+
+impl<T> Versioned<T> {
+    pub fn new(data: T) -> Versioned<T> {
+        Versioned {
+            format_version: FORMAT_VERSION,
+            data,
+        }
+    }
+}
+
+pub fn to_json<T: Serialize>(value: &T) -> Result<String> {
+    match serde_json::to_string(&Versioned::new(value)) {
+        Ok(json) => Ok(json),
+        Err(_) => Err(Error::ParseError),
+    }
+}
+
+pub fn from_json<T: DeserializeOwned>(json: &str) -> Result<T> {
+    let versioned: Versioned<T> = match serde_json::from_str(json) {
+        Ok(versioned) => versioned,
+        Err(_) => return Err(Error::ParseError),
+    };
+
+    if versioned.format_version != FORMAT_VERSION {
+        return Err(Error::ValidationError);
+    }
+
+    Ok(versioned.data)
+}
+
+pub fn save_json<T: Serialize>(path: &Path, value: &T) -> Result<()> {
+    match std::fs::write(path, to_json(value)?) {
+        Ok(_) => Ok(()),
+        Err(_) => Err(Error::IOError),
+    }
+}
+
+pub fn load_json<T: DeserializeOwned>(path: &Path) -> Result<T> {
+    match std::fs::read_to_string(path) {
+        Ok(contents) => from_json(&contents),
+        Err(_) => Err(Error::IOError),
+    }
+}
+
+// Sequences are stored as strings of residue letters with '_' for gaps
+pub(crate) mod sequence {
+    use crate::BioData;
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    pub(crate) fn serialize<S: Serializer, T: BioData>(
+        sequence: &[T],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match T::vec_to_str(sequence) {
+            Ok(string) => serializer.serialize_str(&string),
+            Err(err) => Err(serde::ser::Error::custom(format!("{:?}", err))),
+        }
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>, T: BioData>(
+        deserializer: D,
+    ) -> Result<Vec<T>, D::Error> {
+        let string = String::deserialize(deserializer)?;
+
+        T::str_to_vec(&string).map_err(|err| de::Error::custom(format!("{:?}", err)))
+    }
+}
@@ -0,0 +1,75 @@
+use crate::alignment::{Alignment, PWMAlignment};
+use crate::alignment_result::AlignmentResult;
+use crate::enums::{BioData, Protein, DNA};
+use crate::serialization::{from_json, to_json, Versioned, FORMAT_VERSION};
+use crate::simple::SimpleLocalAligner;
+use crate::statistics::DistributionParams;
+use crate::{get_blosum62, AlignerTrait, Error};
+
+#[test]
+fn alignment_result_round_trip_test() {
+    let matrix = get_blosum62();
+    let result = SimpleLocalAligner::<Protein>::from_str_seqs("MKTWDRYQEFHSIC", "AMKTWDRYQEHSICG")
+        .unwrap()
+        .perform_alignment(11f64, 1f64, &matrix, None)
+        .unwrap();
+
+    let json = to_json(&result).unwrap();
+    let restored: AlignmentResult<Protein, Alignment<Protein>> = from_json(&json).unwrap();
+
+    assert_eq!(restored.alignment.query(), result.alignment.query());
+    assert_eq!(restored.alignment.target(), result.alignment.target());
+    assert_eq!(restored.alignment.coords, result.alignment.coords);
+    assert_eq!(restored.alignment_matrix, result.alignment_matrix);
+    assert_eq!(
+        restored.direction_matrix.dim(),
+        result.direction_matrix.dim()
+    );
+
+    let json = to_json(&result.without_direction_matrix()).unwrap();
+    assert!(!json.contains("direction_matrix"));
+
+    let restored: AlignmentResult<Protein, Alignment<Protein>> = from_json(&json).unwrap();
+    assert!(restored.direction_matrix.is_empty());
+}
+
+#[test]
+fn alignment_sequences_are_strings_test() {
+    let alignment = PWMAlignment {
+        numbered: vec![1, 2, 0],
+        query: DNA::str_to_vec("A_T").unwrap(),
+        dim: 2,
+        coords: ((1, 3), (1, 2)),
+        f: 1f64,
+    };
+
+    let json = to_json(&alignment).unwrap();
+    assert!(json.contains("\"query\":\"A_T\""));
+
+    let restored: PWMAlignment<DNA> = from_json(&json).unwrap();
+    assert_eq!(restored.query(), alignment.query());
+}
+
+#[test]
+fn format_version_test() {
+    let params = DistributionParams {
+        k: 0.041,
+        lambda: 0.267,
+        h: 0.14,
+    };
+
+    let json = serde_json::to_string(&Versioned {
+        format_version: FORMAT_VERSION + 1,
+        data: params,
+    })
+    .unwrap();
+
+    assert!(matches!(
+        from_json::<DistributionParams>(&json),
+        Err(Error::ValidationError)
+    ));
+    assert!(matches!(
+        from_json::<DistributionParams>("{}"),
+        Err(Error::ParseError)
+    ));
+}
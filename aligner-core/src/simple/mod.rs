@@ -6,6 +6,9 @@ use ndarray::Array2;
 use ndarray_stats::QuantileExt;
 use std::marker::PhantomData;
 
+#[cfg(test)]
+mod test;
+
 pub struct SimpleGlobalAligner<T: BioData + Into<usize> + Copy + Eq> {
     pub query: Vec<T>,
     pub target: Vec<T>,
@@ -16,6 +19,13 @@ pub struct SimpleLocalAligner<T: BioData + Into<usize> + Copy + Eq> {
     pub target: Vec<T>,
 }
 
+// The whole query against a part of the target: gaps before and after the
+// query are free, so a short read or domain lands where it fits best
+pub struct SemiGlobalAligner<T: BioData + Into<usize> + Copy + Eq> {
+    pub query: Vec<T>,
+    pub target: Vec<T>,
+}
+
 impl<T: BioData + Into<usize> + Copy + Eq> AlignerTrait<T, Alignment<T>>
     for SimpleGlobalAligner<T>
 {
@@ -99,10 +109,7 @@ impl<T: BioData + Into<usize> + Copy + Eq> AlignerTrait<T, Alignment<T>>
         let mut current_x = self.query.len();
         let mut current_y = self.target.len();
 
-        let (mut query_aligned, mut target_aligned) = (
-            vec![*self.query.last().unwrap()],
-            vec![*self.target.last().unwrap()],
-        );
+        let (mut query_aligned, mut target_aligned) = (vec![], vec![]);
 
         loop {
             match direction_matrix[[current_y, current_x]] {
@@ -129,14 +136,16 @@ impl<T: BioData + Into<usize> + Copy + Eq> AlignerTrait<T, Alignment<T>>
         query_aligned.reverse();
         target_aligned.reverse();
 
+        let f = alignment_matrix[[self.target.len(), self.query.len()]];
+
         Ok(AlignmentResult {
             alignment_matrix,
             direction_matrix,
             alignment: Alignment {
                 query: query_aligned,
                 target: target_aligned,
-                coords: ((1, self.query.len()), (1, self.target.len())),
-                f: 0f64,
+                coords: ((1, self.query.len() + 1), (1, self.target.len() + 1)),
+                f,
             },
             phantom: PhantomData,
             matrix: None,
@@ -210,10 +219,7 @@ impl<T: BioData + Into<usize> + Copy + Eq> AlignerTrait<T, Alignment<T>> for Sim
         }
 
         let max_coords = alignment_matrix.argmax().unwrap();
-        let (mut query_alignment, mut target_alignment) = (
-            vec![self.query[max_coords.1 - 1]],
-            vec![self.target[max_coords.0 - 1]],
-        );
+        let (mut query_alignment, mut target_alignment) = (vec![], vec![]);
         let mut current_x = max_coords.1;
         let mut current_y = max_coords.0;
 
@@ -263,3 +269,118 @@ impl<T: BioData + Into<usize> + Copy + Eq> AlignerTrait<T, Alignment<T>> for Sim
         })
     }
 }
+
+impl<T: BioData + Into<usize> + Copy + Eq> AlignerTrait<T, Alignment<T>> for SemiGlobalAligner<T> {
+    fn from_str_seqs(query: &str, target: &str) -> Result<SemiGlobalAligner<T>> {
+        Ok(SemiGlobalAligner {
+            query: T::str_to_vec(query)?,
+            target: T::str_to_vec(target)?,
+        })
+    }
+
+    fn from_seqs(query: &[T], target: &[T]) -> Result<SemiGlobalAligner<T>> {
+        Ok(SemiGlobalAligner {
+            query: Vec::from(query),
+            target: Vec::from(target),
+        })
+    }
+
+    fn perform_alignment(
+        &mut self,
+        del: f64,
+        ext: f64,
+        matrix: &Array2<f64>,
+        heuristics: Option<Heuristics>,
+    ) -> Result<AlignmentResult<T, Alignment<T>>> {
+        if heuristics.is_some() {
+            return Err(Error::UnnecessaryArgument);
+        };
+
+        let dim = (self.target.len() + 1, self.query.len() + 1);
+
+        // The first column stays zero, so the query may start anywhere
+        let mut alignment_matrix = Array2::<f64>::zeros(dim);
+        let mut direction_matrix =
+            Array2::<Direction>::from_shape_fn(dim, |_| Direction::Beginning);
+
+        for x in 1..self.query.len() + 1 {
+            alignment_matrix[[0, x]] = -(x as f64) * del;
+            direction_matrix[[0, x]] = Direction::Left
+        }
+
+        let mut penalty = del;
+
+        for (x, elem_1) in self.query.iter().enumerate() {
+            for (y, elem_2) in self.target.iter().enumerate() {
+                let x_real = x + 1;
+                let y_real = y + 1;
+
+                let seq_1_pos: usize = (*elem_1).into();
+                let seq_2_pos: usize = (*elem_2).into();
+
+                let assignment = Direction::get_direction(
+                    alignment_matrix[[y_real - 1, x_real]] - penalty,
+                    alignment_matrix[[y_real, x_real - 1]] - penalty,
+                    alignment_matrix[[y_real - 1, x_real - 1]] + matrix[[seq_2_pos, seq_1_pos]],
+                );
+
+                if assignment.1 != Direction::Beginning {
+                    penalty = ext
+                } else {
+                    penalty = del
+                }
+
+                alignment_matrix[[y_real, x_real]] = assignment.0;
+                direction_matrix[[y_real, x_real]] = assignment.1;
+            }
+        }
+
+        // ...and end anywhere, at the best row of the last column
+        let mut current_x = self.query.len();
+        let mut current_y = alignment_matrix
+            .column(current_x)
+            .argmax()
+            .map_err(|_| Error::MatrixShapeError)?;
+        let (end_y, f) = (current_y, alignment_matrix[[current_y, current_x]]);
+
+        let (mut query_aligned, mut target_aligned) = (vec![], vec![]);
+
+        loop {
+            match direction_matrix[[current_y, current_x]] {
+                Direction::Beginning => break,
+                Direction::Top => {
+                    query_aligned.push(T::blank());
+                    target_aligned.push(self.target[current_y - 1]);
+                    current_y -= 1;
+                }
+                Direction::Left => {
+                    query_aligned.push(self.query[current_x - 1]);
+                    target_aligned.push(T::blank());
+                    current_x -= 1;
+                }
+                Direction::Diagonal => {
+                    query_aligned.push(self.query[current_x - 1]);
+                    target_aligned.push(self.target[current_y - 1]);
+                    current_x -= 1;
+                    current_y -= 1;
+                }
+            }
+        }
+
+        query_aligned.reverse();
+        target_aligned.reverse();
+
+        Ok(AlignmentResult {
+            alignment_matrix,
+            direction_matrix,
+            alignment: Alignment {
+                query: query_aligned,
+                target: target_aligned,
+                coords: ((1, self.query.len() + 1), (current_y + 1, end_y + 1)),
+                f,
+            },
+            phantom: PhantomData,
+            matrix: None,
+        })
+    }
+}
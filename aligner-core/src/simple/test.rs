@@ -0,0 +1,68 @@
+use crate::enums::{BioData, DNA};
+use crate::get_dna_matrix;
+use crate::simple::{SemiGlobalAligner, SimpleGlobalAligner, SimpleLocalAligner};
+use crate::AlignerTrait;
+
+#[test]
+fn semi_global_alignment_test() {
+    let matrix = get_dna_matrix(2f64, -3f64);
+    let mut aligner = SemiGlobalAligner::<DNA>::from_str_seqs("ACGA", "TTACGATT").unwrap();
+    let result = aligner
+        .perform_alignment(5f64, 2f64, &matrix, None)
+        .unwrap();
+
+    assert_eq!(result.alignment.query, result.alignment.target);
+    assert_eq!(result.alignment.coords, ((1, 5), (3, 7)));
+    assert_eq!(result.alignment.f, 8f64);
+
+    // Overhanging query bases are paid for
+    let mut aligner = SemiGlobalAligner::<DNA>::from_str_seqs("GGACGA", "ACGATT").unwrap();
+    let result = aligner
+        .perform_alignment(5f64, 2f64, &matrix, None)
+        .unwrap();
+
+    assert_eq!(result.alignment.query.len(), 6);
+    assert_eq!(result.alignment.coords.1, (1, 5));
+}
+
+#[test]
+fn global_traceback_test() {
+    let matrix = get_dna_matrix(2f64, -3f64);
+    let mut aligner = SimpleGlobalAligner::<DNA>::from_str_seqs("ACGT", "ACGT").unwrap();
+    let result = aligner
+        .perform_alignment(5f64, 2f64, &matrix, None)
+        .unwrap();
+
+    // The last pair is aligned once
+    assert_eq!(result.alignment.query, DNA::str_to_vec("ACGT").unwrap());
+    assert_eq!(result.alignment.target, DNA::str_to_vec("ACGT").unwrap());
+    assert_eq!(result.alignment.coords, ((1, 5), (1, 5)));
+}
+
+#[test]
+fn global_score_test() {
+    let matrix = get_dna_matrix(2f64, -3f64);
+
+    for (target, f) in [("ACGT", 8f64), ("ACCT", 3f64)] {
+        let mut aligner = SimpleGlobalAligner::<DNA>::from_str_seqs("ACGT", target).unwrap();
+        let result = aligner
+            .perform_alignment(5f64, 2f64, &matrix, None)
+            .unwrap();
+
+        assert_eq!(result.alignment.f, f);
+    }
+}
+
+#[test]
+fn local_traceback_test() {
+    let matrix = get_dna_matrix(2f64, -3f64);
+    let mut aligner = SimpleLocalAligner::<DNA>::from_str_seqs("ACGT", "ACGT").unwrap();
+    let result = aligner
+        .perform_alignment(5f64, 2f64, &matrix, None)
+        .unwrap();
+
+    assert_eq!(result.alignment.query, DNA::str_to_vec("ACGT").unwrap());
+    assert_eq!(result.alignment.target, DNA::str_to_vec("ACGT").unwrap());
+    assert_eq!(result.alignment.coords, ((1, 5), (1, 5)));
+    assert_eq!(result.alignment.f, 8f64);
+}
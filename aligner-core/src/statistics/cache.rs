@@ -0,0 +1,172 @@
+use crate::statistics::{DistributionParams, PValueOptions};
+use crate::{BioData, Error, Result};
+use ndarray::Array2;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+const FNV_OFFSET: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+// Length buckets are spaced by a factor of sqrt(2)
+const BUCKETS_PER_OCTAVE: f64 = 2f64;
+const COMPOSITION_CLASSES: f64 = 10f64;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheEntry {
+    bucket: i32,
+    params: DistributionParams,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ParamsCache {
+    entries: HashMap<String, Vec<CacheEntry>>,
+}
+
+impl ParamsCache {
+    pub fn load(path: &Path) -> Result<ParamsCache> {
+        if !path.exists() {
+            return Ok(ParamsCache::default());
+        }
+
+        let contents = match std::fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(_) => return Err(Error::IOError),
+        };
+
+        match serde_json::from_str(&contents) {
+            Ok(cache) => Ok(cache),
+            Err(_) => Err(Error::ParseError),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let contents = match serde_json::to_string_pretty(self) {
+            Ok(contents) => contents,
+            Err(_) => return Err(Error::ParseError),
+        };
+
+        match std::fs::write(path, contents) {
+            Ok(_) => Ok(()),
+            Err(_) => Err(Error::IOError),
+        }
+    }
+
+    pub fn get(&self, key: &str, length: usize) -> Option<DistributionParams> {
+        let entries = self.entries.get(key)?;
+        let position = get_position(length);
+
+        if let Some(entry) = entries
+            .iter()
+            .find(|entry| entry.bucket == position.round() as i32)
+        {
+            return Some(entry.params.clone());
+        }
+
+        let lower = entries
+            .iter()
+            .filter(|entry| entry.bucket as f64 <= position)
+            .max_by_key(|entry| entry.bucket)?;
+        let upper = entries
+            .iter()
+            .filter(|entry| entry.bucket as f64 >= position)
+            .min_by_key(|entry| entry.bucket)?;
+
+        let t = (position - lower.bucket as f64) / (upper.bucket - lower.bucket) as f64;
+        let interpolate = |u: f64, v: f64| u + (v - u) * t;
+
+        Some(DistributionParams {
+            k: interpolate(lower.params.k.ln(), upper.params.k.ln()).exp(),
+            lambda: interpolate(lower.params.lambda, upper.params.lambda),
+            h: interpolate(lower.params.h, upper.params.h),
+        })
+    }
+
+    pub fn get_or_insert_with<F>(
+        &mut self,
+        key: &str,
+        length: usize,
+        f: F,
+    ) -> Result<DistributionParams>
+    where
+        F: FnOnce() -> Result<DistributionParams>,
+    {
+        if let Some(params) = self.get(key, length) {
+            debug!("Using cached distribution params for {}", key);
+            return Ok(params);
+        }
+
+        let params = f()?;
+        self.insert(key, length, params.clone());
+
+        Ok(params)
+    }
+
+    pub fn insert(&mut self, key: &str, length: usize, params: DistributionParams) {
+        let bucket = get_position(length).round() as i32;
+        let entries = self.entries.entry(key.to_string()).or_default();
+
+        entries.retain(|entry| entry.bucket != bucket);
+        entries.push(CacheEntry { bucket, params });
+    }
+}
+
+// Options that change the fitted params are part of the key, threads, seeds
+// and the bootstrap only change how they are estimated
+pub fn get_cache_key<T: BioData + Into<usize> + Copy>(
+    matrix: &Array2<f64>,
+    del: f64,
+    ext: f64,
+    target: &[T],
+    opts: &PValueOptions,
+) -> String {
+    format!(
+        "{:016x}:{}:{}:{}:{:?}:{}:{}",
+        get_matrix_fingerprint(matrix),
+        del,
+        ext,
+        get_composition_class(target),
+        opts.null_model,
+        opts.sequences,
+        opts.composition_adjustment.is_some()
+    )
+}
+
+pub fn get_matrix_fingerprint(matrix: &Array2<f64>) -> u64 {
+    let (rows, columns) = matrix.dim();
+
+    [rows as u64, columns as u64]
+        .into_iter()
+        .chain(matrix.iter().map(|value| value.to_bits()))
+        .flat_map(|value| value.to_le_bytes())
+        .fold(FNV_OFFSET, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(FNV_PRIME)
+        })
+}
+
+// Normalised Shannon entropy of the residue frequencies, split into equal bins
+pub fn get_composition_class<T: BioData + Into<usize> + Copy>(sequence: &[T]) -> usize {
+    let mut counts = vec![0f64; T::volume()];
+    for elem in sequence.iter() {
+        let a: usize = (*elem).into();
+        if a < counts.len() {
+            counts[a] += 1f64;
+        }
+    }
+
+    let total = counts.iter().sum::<f64>();
+    if total == 0f64 || T::volume() < 2 {
+        return 0;
+    }
+
+    let entropy = -counts
+        .iter()
+        .filter(|count| **count > 0f64)
+        .map(|count| count / total * (count / total).ln())
+        .sum::<f64>();
+
+    (entropy / (T::volume() as f64).ln() * COMPOSITION_CLASSES).floor() as usize
+}
+
+fn get_position(length: usize) -> f64 {
+    (length.max(1) as f64).log2() * BUCKETS_PER_OCTAVE
+}
@@ -0,0 +1,58 @@
+use crate::statistics::karlin::calculate_ungapped_params;
+use crate::{BioData, Error, Result};
+use ndarray::{Array1, Array2};
+
+// Residue frequencies of a sequence over the full alphabet, summing to one
+pub fn get_composition<T: BioData + Into<usize> + Copy>(sequence: &[T]) -> Array1<f64> {
+    let mut counts = Array1::<f64>::zeros(T::volume());
+
+    for elem in sequence.iter() {
+        let a: usize = (*elem).into();
+        if a < counts.len() {
+            counts[a] += 1f64;
+        }
+    }
+
+    let total = counts.sum();
+    if total > 0f64 {
+        counts /= total;
+    }
+
+    counts
+}
+
+// Composition with a pseudocount added to every residue, so that none of them
+// is impossible in short or strongly biased sequences
+pub fn get_smoothed_composition<T: BioData + Into<usize> + Copy>(
+    sequence: &[T],
+    pseudocount: f64,
+) -> Array1<f64> {
+    let mut counts = get_composition(sequence) * sequence.len() as f64 + pseudocount;
+
+    let total = counts.sum();
+    if total > 0f64 {
+        counts /= total;
+    }
+
+    counts
+}
+
+// Composition-based statistics (Schaffer et al., 2001): the matrix is scaled so
+// that its ungapped lambda under the actual compositions equals the one under
+// the background, which keeps the precomputed K and lambda valid for biased pairs
+pub fn rescale_matrix(
+    matrix: &Array2<f64>,
+    background: &Array1<f64>,
+    query_freqs: &Array1<f64>,
+    target_freqs: &Array1<f64>,
+) -> Result<Array2<f64>> {
+    let standard = calculate_ungapped_params(matrix, background, background)?;
+    let actual = calculate_ungapped_params(matrix, query_freqs, target_freqs)?;
+
+    let factor = actual.lambda / standard.lambda;
+    if !factor.is_finite() || factor <= 0f64 {
+        return Err(Error::CalculationError);
+    }
+
+    Ok(matrix.mapv(|score| (score * factor).round()))
+}
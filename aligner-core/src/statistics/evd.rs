@@ -0,0 +1,103 @@
+use crate::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+
+const EULER: f64 = 0.5772156649015329;
+const MAXITER: usize = 100;
+const THRESHOLD: f64 = 1e-8;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GumbelParams {
+    pub mu: f64,
+    pub beta: f64,
+}
+
+impl GumbelParams {
+    pub fn fit(scores: &[f64]) -> Result<GumbelParams> {
+        if scores.len() < 2 || scores.iter().any(|score| !score.is_finite()) {
+            return Err(Error::ValidationError);
+        }
+
+        let n = scores.len() as f64;
+        let mean = scores.iter().sum::<f64>() / n;
+        let sd = (scores.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / n).sqrt();
+
+        if sd == 0f64 {
+            return Err(Error::ValidationError);
+        }
+
+        let offset = scores.iter().cloned().fold(f64::INFINITY, f64::min);
+
+        // weights are taken relative to the lowest score to keep exp() finite
+        let moments = |beta: f64| {
+            let (mut w, mut xw, mut xxw) = (0f64, 0f64, 0f64);
+            for x in scores.iter() {
+                let e = (-(x - offset) / beta).exp();
+                w += e;
+                xw += x * e;
+                xxw += x * x * e;
+            }
+            (w, xw / w, xxw / w)
+        };
+
+        let mut beta = 6f64.sqrt() * sd / PI;
+
+        for _ in 0..MAXITER {
+            let (_, first, second) = moments(beta);
+
+            let g = beta - mean + first;
+            let gd = 1f64 + (second - first * first) / (beta * beta);
+
+            let new_beta = beta - g / gd;
+            if !new_beta.is_finite() || new_beta <= 0f64 {
+                return Err(Error::CalculationError);
+            }
+
+            let converged = (new_beta - beta).abs() < THRESHOLD * beta;
+            beta = new_beta;
+
+            if converged {
+                break;
+            }
+        }
+
+        let (w, _, _) = moments(beta);
+
+        Ok(GumbelParams {
+            mu: offset - beta * (w / n).ln(),
+            beta,
+        })
+    }
+
+    pub fn get_p_value(&self, score: f64) -> f64 {
+        let tail = (-(score - self.mu) / self.beta).exp();
+
+        -(-tail).exp_m1()
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.mu + EULER * self.beta
+    }
+
+    pub fn std(&self) -> f64 {
+        PI * self.beta / 6f64.sqrt()
+    }
+}
+
+// Benjamini-Hochberg adjusted p-values, returned in the input order
+pub fn calculate_q_values(p_values: &[f64]) -> Vec<f64> {
+    let n = p_values.len();
+
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|u, v| p_values[*u].partial_cmp(&p_values[*v]).unwrap());
+
+    let mut q_values = vec![0f64; n];
+    let mut current = 1f64;
+
+    for (rank, i) in order.iter().enumerate().rev() {
+        current = current.min(p_values[*i] * n as f64 / (rank + 1) as f64);
+        q_values[*i] = current;
+    }
+
+    q_values
+}
@@ -0,0 +1,183 @@
+use crate::statistics::DistributionParams;
+use crate::{get_blosum62, Error, Result};
+use ndarray::{Array1, Array2};
+
+const LAMBDA_ITERATIONS: usize = 100;
+const K_ITERATIONS: usize = 100;
+const THRESHOLD: f64 = 1e-12;
+
+// (open, extend, lambda, k, h) as published for BLAST, where a gap of length L
+// costs open + L * extend
+const BLOSUM62_GAPPED: [(f64, f64, f64, f64, f64); 11] = [
+    (11f64, 2f64, 0.297, 0.082, 0.27),
+    (10f64, 2f64, 0.291, 0.075, 0.23),
+    (9f64, 2f64, 0.279, 0.058, 0.19),
+    (8f64, 2f64, 0.264, 0.045, 0.15),
+    (7f64, 2f64, 0.239, 0.027, 0.10),
+    (6f64, 2f64, 0.201, 0.012, 0.061),
+    (13f64, 1f64, 0.292, 0.071, 0.23),
+    (12f64, 1f64, 0.283, 0.059, 0.19),
+    (11f64, 1f64, 0.267, 0.041, 0.14),
+    (10f64, 1f64, 0.243, 0.024, 0.10),
+    (9f64, 1f64, 0.206, 0.010, 0.052),
+];
+
+pub fn calculate_ungapped_params(
+    matrix: &Array2<f64>,
+    query_freqs: &Array1<f64>,
+    target_freqs: &Array1<f64>,
+) -> Result<DistributionParams> {
+    if matrix.dim() != (target_freqs.len(), query_freqs.len()) {
+        return Err(Error::MatrixShapeError);
+    }
+
+    if matrix.iter().any(|score| score.fract() != 0f64) {
+        return Err(Error::ValidationError);
+    }
+
+    let (query_total, target_total) = (query_freqs.sum(), target_freqs.sum());
+    if query_total <= 0f64 || target_total <= 0f64 {
+        return Err(Error::ValidationError);
+    }
+
+    let low = matrix.iter().cloned().fold(f64::INFINITY, f64::min) as i64;
+    let high = matrix.iter().cloned().fold(f64::NEG_INFINITY, f64::max) as i64;
+
+    let mut probabilities = vec![0f64; (high - low + 1) as usize];
+    for ((y, x), score) in matrix.indexed_iter() {
+        probabilities[(*score as i64 - low) as usize] +=
+            target_freqs[y] / target_total * query_freqs[x] / query_total;
+    }
+
+    let scores: Vec<(f64, f64)> = probabilities
+        .iter()
+        .enumerate()
+        .filter(|(_, p)| **p > 0f64)
+        .map(|(i, p)| ((i as i64 + low) as f64, *p))
+        .collect();
+
+    let expected = scores.iter().map(|(s, p)| s * p).sum::<f64>();
+    if expected >= 0f64 || scores.iter().all(|(s, _)| *s <= 0f64) {
+        return Err(Error::ValidationError);
+    }
+
+    let lambda = solve_lambda(&scores);
+
+    let h = lambda
+        * scores
+            .iter()
+            .map(|(s, p)| s * p * (lambda * s).exp())
+            .sum::<f64>();
+
+    let delta = scores
+        .iter()
+        .fold(0i64, |acc, (s, _)| gcd(acc, s.abs() as i64)) as f64;
+
+    let sigma = calculate_sigma(&probabilities, low, lambda);
+
+    let k = delta * lambda * (-2f64 * sigma).exp() / (h * (1f64 - (-lambda * delta).exp()));
+
+    if !k.is_finite() || k <= 0f64 {
+        return Err(Error::CalculationError);
+    }
+
+    Ok(DistributionParams { k, lambda, h })
+}
+
+// Gap penalties follow the aligners here: the first gap position costs del and
+// every following one costs ext. The table was fitted for true affine gaps,
+// while the aligners carry a single penalty from cell to cell, so the values
+// only approximate the statistics of their scores
+pub fn get_gapped_params(matrix: &Array2<f64>, del: f64, ext: f64) -> Option<DistributionParams> {
+    if *matrix != get_blosum62() {
+        return None;
+    }
+
+    BLOSUM62_GAPPED
+        .iter()
+        .find(|(open, extend, ..)| {
+            (open + extend - del).abs() < f64::EPSILON && (extend - ext).abs() < f64::EPSILON
+        })
+        .map(|(_, _, lambda, k, h)| DistributionParams {
+            k: *k,
+            lambda: *lambda,
+            h: *h,
+        })
+}
+
+fn solve_lambda(scores: &[(f64, f64)]) -> f64 {
+    let f = |lambda: f64| {
+        scores
+            .iter()
+            .map(|(s, p)| p * (lambda * s).exp())
+            .sum::<f64>()
+            - 1f64
+    };
+
+    let (mut lower, mut upper) = (0f64, 0.5);
+    while f(upper) < 0f64 {
+        lower = upper;
+        upper *= 2f64;
+    }
+
+    for _ in 0..LAMBDA_ITERATIONS {
+        let middle = (lower + upper) / 2f64;
+
+        if f(middle) < 0f64 {
+            lower = middle;
+        } else {
+            upper = middle;
+        }
+    }
+
+    (lower + upper) / 2f64
+}
+
+// sigma = sum over k of E[exp(lambda * S_k); S_k < 0] + P(S_k >= 0), divided by
+// k, where S_k is the sum of k independent scores (Karlin & Altschul, 1990)
+fn calculate_sigma(probabilities: &[f64], low: i64, lambda: f64) -> f64 {
+    let mut sigma = 0f64;
+    let mut distribution = vec![1f64];
+    let mut offset = 0i64;
+
+    for k in 1..=K_ITERATIONS {
+        let mut next = vec![0f64; distribution.len() + probabilities.len() - 1];
+        for (i, u) in distribution.iter().enumerate() {
+            for (j, v) in probabilities.iter().enumerate() {
+                next[i + j] += u * v;
+            }
+        }
+        distribution = next;
+        offset += low;
+
+        let term = distribution
+            .iter()
+            .enumerate()
+            .map(|(i, p)| {
+                let score = i as i64 + offset;
+                if score < 0 {
+                    p * (lambda * score as f64).exp()
+                } else {
+                    *p
+                }
+            })
+            .sum::<f64>()
+            / k as f64;
+
+        sigma += term;
+
+        if term < THRESHOLD {
+            break;
+        }
+    }
+
+    sigma
+}
+
+fn gcd(a: i64, b: i64) -> i64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
@@ -1,18 +1,33 @@
+use crate::pwm::PWMAligner;
 use crate::simple::SimpleLocalAligner;
-use crate::{AlignerTrait, Error, Protein, Result};
-use ndarray::{arr1, Array1, Array2, Zip};
+use crate::statistics::composition::{get_composition, rescale_matrix};
+use crate::statistics::karlin::get_gapped_params;
+use crate::statistics::null_model::NullModel;
+use crate::{get_rng, get_thread_seeds, AlignerTrait, BioData, Error, Result};
+use ndarray::{arr1, Array1, Array2, Axis, Zip};
 use ndarray_stats::SummaryStatisticsExt;
-use rand::seq::SliceRandom;
-use rand::{thread_rng, Rng};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::f64::consts::{LN_2, PI};
 use std::thread;
 
+pub mod cache;
+pub mod composition;
+pub mod evd;
+pub mod karlin;
+pub mod null_model;
+#[cfg(test)]
+mod test;
+
 const MAXITER: i32 = 10000;
-const THREADS: i32 = 10;
-const SEQUENCES: i32 = 5000;
+const THREADS: usize = 10;
+const SEQUENCES: usize = 5000;
 const THRESHOLD_GLOBAL: f64 = 1e-6;
 const THRESHOLD_LOCAL: f64 = 1e-4;
+const CONFIDENCE_LEVEL: f64 = 0.95;
+const MIN_SCORES_KEPT: usize = 50;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DistributionParams {
     pub k: f64,
     pub lambda: f64,
@@ -20,7 +35,7 @@ pub struct DistributionParams {
 }
 
 impl DistributionParams {
-    fn get_p_value(&self, query_length: usize, target_length: usize, score: f64) -> f64 {
+    pub fn get_p_value(&self, query_length: usize, target_length: usize, score: f64) -> f64 {
         let l = (self.k * query_length as f64 * target_length as f64).ln() / self.h;
         let nn = (query_length as f64 - l) * (target_length as f64 - l);
 
@@ -31,6 +46,56 @@ impl DistributionParams {
 
         1f64 - (-self.k * nn * (-self.lambda * score).exp()).exp()
     }
+
+    pub fn get_bit_score(&self, score: f64) -> f64 {
+        (self.lambda * score - self.k.ln()) / LN_2
+    }
+
+    pub fn get_search_space(
+        &self,
+        query_length: usize,
+        database_length: usize,
+        database_sequences: usize,
+    ) -> f64 {
+        let l = (self.k * query_length as f64 * database_length as f64).ln() / self.h;
+
+        let m = (query_length as f64 - l).max(1f64 / self.k);
+        let n = (database_length as f64 - database_sequences as f64 * l).max(1f64 / self.k);
+
+        m * n
+    }
+
+    pub fn get_e_value(&self, search_space: f64, score: f64) -> f64 {
+        self.k * search_space * (-self.lambda * score).exp()
+    }
+
+    pub fn is_finite(&self) -> bool {
+        self.k.is_finite() && self.lambda.is_finite() && self.h.is_finite()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConvergenceReport {
+    pub iterations: usize,
+    pub converged: bool,
+    pub log_likelihood: f64,
+    pub scores_kept: usize,
+    pub scores_total: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Interval {
+    pub lower: f64,
+    pub upper: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParamsConfidence {
+    pub level: f64,
+    pub k: Interval,
+    pub lambda: Interval,
+    pub h: Interval,
+    pub p_value: Interval,
 }
 
 pub fn calculate_distribution_params(
@@ -38,23 +103,32 @@ pub fn calculate_distribution_params(
     target_lengths: &Array1<usize>,
     scores: &Array1<f64>,
 ) -> Result<DistributionParams> {
+    Ok(fit_distribution_params(query_length, target_lengths, scores)?.0)
+}
+
+pub fn fit_distribution_params(
+    query_length: usize,
+    target_lengths: &Array1<usize>,
+    scores: &Array1<f64>,
+) -> Result<(DistributionParams, ConvergenceReport)> {
     if scores.len() != target_lengths.len() {
         return Err(Error::ValidationError);
     }
 
     let sd = match scores.central_moment(2) {
-        Ok(sd) => sd,
+        Ok(variance) => variance.sqrt(),
         Err(_) => return Err(Error::ValidationError),
     };
 
-    let lambda = 1f64 / sd;
+    // moment estimate of the Gumbel scale
+    let mut lambda = PI / (sd * 6f64.sqrt());
     let mut h = 1f64;
 
     let n = target_lengths.len() as f64;
 
     let mut nn_array = target_lengths.mapv(|t| (query_length * t) as f64);
 
-    let k = n / (&nn_array * scores.mapv(|score| (-lambda * score).exp())).sum();
+    let mut k = n / (&nn_array * scores.mapv(|score| (-lambda * score).exp())).sum();
 
     let mut log_likelihood = n * (lambda * k).ln()
         + Zip::from(&nn_array)
@@ -65,8 +139,8 @@ pub fn calculate_distribution_params(
     let mut active_target_lengths = target_lengths.clone();
     let mut active_scores = scores.clone();
 
-    for _ in 0..=MAXITER {
-        let (k, lambda) = estimate_k_and_lambda_by_parameters(
+    for i in 0..=MAXITER {
+        (k, lambda) = estimate_k_and_lambda_by_parameters(
             query_length,
             &active_target_lengths,
             &active_scores,
@@ -90,14 +164,25 @@ pub fn calculate_distribution_params(
             (query_length as f64 - l) * (t as f64 - l)
         });
 
-        let log_likelihood_new = n * (lambda * k).log10()
-            + (nn_array.mapv(|nn| nn.log10())
+        let log_likelihood_new = n * (lambda * k).ln()
+            + (nn_array.mapv(|nn| nn.ln())
                 - lambda * scores
                 - k * &nn_array * scores.mapv(|score| (-lambda * score).exp()))
             .sum();
 
-        if (log_likelihood_new - log_likelihood).abs() / log_likelihood < THRESHOLD_GLOBAL {
-            return Ok(DistributionParams { k, lambda, h });
+        if (log_likelihood_new - log_likelihood).abs() / log_likelihood.abs() < THRESHOLD_GLOBAL {
+            let params = DistributionParams { k, lambda, h };
+
+            return Ok((
+                params.clone(),
+                ConvergenceReport {
+                    iterations: i as usize + 1,
+                    converged: params.is_finite(),
+                    log_likelihood: log_likelihood_new,
+                    scores_kept: active_scores.len(),
+                    scores_total: scores.len(),
+                },
+            ));
         }
 
         log_likelihood = log_likelihood_new;
@@ -119,7 +204,83 @@ pub fn calculate_distribution_params(
         active_scores = arr1(&scores_buffer);
     }
 
-    Ok(DistributionParams { k, lambda, h })
+    warn!(
+        "Distribution params did not converge in {} iterations",
+        MAXITER + 1
+    );
+
+    Ok((
+        DistributionParams { k, lambda, h },
+        ConvergenceReport {
+            iterations: MAXITER as usize + 1,
+            converged: false,
+            log_likelihood,
+            scores_kept: active_scores.len(),
+            scores_total: scores.len(),
+        },
+    ))
+}
+
+pub fn bootstrap_distribution_params<R: Rng + ?Sized>(
+    query_length: usize,
+    target_lengths: &Array1<usize>,
+    scores: &Array1<f64>,
+    target_length: usize,
+    initial_score: f64,
+    opts: &PValueOptions,
+    rng: &mut R,
+) -> Result<ParamsConfidence> {
+    let n = scores.len();
+
+    if n != target_lengths.len()
+        || opts.bootstrap == 0
+        || !(0f64..1f64).contains(&opts.confidence_level)
+    {
+        return Err(Error::ValidationError);
+    }
+
+    let mut samples = vec![];
+
+    for _ in 0..opts.bootstrap {
+        let picks: Vec<usize> = (0..n).map(|_| rng.gen_range(0..n)).collect();
+
+        let resampled_lengths = picks.iter().map(|i| target_lengths[*i]).collect();
+        let resampled_scores = picks.iter().map(|i| scores[*i]).collect();
+
+        if let Ok((params, report)) =
+            fit_distribution_params(query_length, &resampled_lengths, &resampled_scores)
+        {
+            if report.converged {
+                let p_value = params.get_p_value(query_length, target_length, initial_score);
+                samples.push((params, p_value));
+            }
+        }
+    }
+
+    if samples.len() * 2 < opts.bootstrap {
+        return Err(Error::CalculationError);
+    }
+
+    let interval = |values: Vec<f64>| get_percentile_interval(values, opts.confidence_level);
+
+    Ok(ParamsConfidence {
+        level: opts.confidence_level,
+        k: interval(samples.iter().map(|(params, _)| params.k).collect()),
+        lambda: interval(samples.iter().map(|(params, _)| params.lambda).collect()),
+        h: interval(samples.iter().map(|(params, _)| params.h).collect()),
+        p_value: interval(samples.iter().map(|(_, p_value)| *p_value).collect()),
+    })
+}
+
+fn get_percentile_interval(mut values: Vec<f64>, level: f64) -> Interval {
+    values.sort_by(|u, v| u.partial_cmp(v).unwrap());
+
+    let quantile = |q: f64| values[((values.len() - 1) as f64 * q).round() as usize];
+
+    Interval {
+        lower: quantile((1f64 - level) / 2f64),
+        upper: quantile((1f64 + level) / 2f64),
+    }
 }
 
 fn estimate_k_and_lambda_by_parameters(
@@ -157,7 +318,7 @@ fn estimate_k_and_lambda_by_parameters(
 
         let new_lambda = lambda - lambda_f / lambda_fd;
 
-        exponential_scores = scores.mapv(|score| (-lambda * score).exp());
+        exponential_scores = scores.mapv(|score| (-new_lambda * score).exp());
         sum = (&nn_array * &exponential_scores).sum();
         weighted_sum = (&nn_array * scores * &exponential_scores).sum();
 
@@ -237,84 +398,264 @@ fn estimate_h_by_parameters(
     h
 }
 
-pub fn calculate_p_value(
-    query: Vec<Protein>,
-    target: Vec<Protein>,
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PValueOptions {
+    pub sequences: usize,
+    pub threads: usize,
+    pub seed: Option<u64>,
+    pub null_model: NullModel,
+    pub bootstrap: usize,
+    pub confidence_level: f64,
+    // Background to rescale the matrix from to the compositions of the query
+    // and the target, for scores made with an adjusted matrix
+    pub composition_adjustment: Option<Array1<f64>>,
+}
+
+impl Default for PValueOptions {
+    fn default() -> Self {
+        PValueOptions {
+            sequences: SEQUENCES,
+            threads: THREADS,
+            seed: None,
+            null_model: NullModel::default(),
+            bootstrap: 0,
+            confidence_level: CONFIDENCE_LEVEL,
+            composition_adjustment: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Significance {
+    pub p_value: f64,
+    pub params: DistributionParams,
+    pub seed: Option<u64>,
+    pub report: Option<ConvergenceReport>,
+    pub confidence: Option<ParamsConfidence>,
+}
+
+impl Significance {
+    pub fn from_params(
+        params: DistributionParams,
+        query_length: usize,
+        target_length: usize,
+        score: f64,
+    ) -> Significance {
+        Significance {
+            p_value: params.get_p_value(query_length, target_length, score),
+            params,
+            seed: None,
+            report: None,
+            confidence: None,
+        }
+    }
+
+    pub fn is_reliable(&self) -> bool {
+        if !self.p_value.is_finite() || !self.params.is_finite() {
+            return false;
+        }
+
+        if let Some(report) = &self.report {
+            if !report.converged || report.scores_kept < MIN_SCORES_KEPT {
+                return false;
+            }
+        }
+
+        match &self.confidence {
+            Some(confidence) => {
+                confidence.p_value.lower.is_finite() && confidence.p_value.upper.is_finite()
+            }
+            None => true,
+        }
+    }
+}
+
+pub fn estimate_p_value<T, F>(
+    query_length: usize,
+    target: &[T],
     initial_score: f64,
-    del: f64,
-    ins: f64,
-    matrix: &Array2<f64>,
-) -> Result<f64> {
+    scorer: F,
+    opts: &PValueOptions,
+) -> Result<Significance>
+where
+    T: BioData + Into<usize> + Copy + Send + Sync,
+    F: Fn(&[T]) -> Result<f64> + Sync,
+{
+    if opts.sequences < 2 || opts.threads == 0 || target.is_empty() {
+        return Err(Error::ValidationError);
+    }
+
     debug!("Started shuffling and calculating input sequences!!");
 
     let mut scores = vec![initial_score];
     let mut lengths = vec![target.len()];
 
-    let mut threads = vec![];
-
-    for i in 0..THREADS {
-        threads.push(thread::spawn({
-            let query_clone = query.clone();
-            let target_clone = target.clone();
-            let matrix_clone = matrix.clone();
-            let mut scores_scoped = Vec::<f64>::new();
-            let mut lengths_scoped = Vec::<usize>::new();
-            move || {
-                let mut limit = SEQUENCES / THREADS;
-                if i == 5 {
-                    limit = SEQUENCES - (SEQUENCES / THREADS * (THREADS - 1)) - 1;
-                }
+    let samples = opts.sequences - 1;
+
+    let seed = opts.seed.unwrap_or_else(rand::random);
+    debug!("Shuffling with seed {}", seed);
+    let mut rng = get_rng(seed);
+    let thread_seeds = get_thread_seeds(&mut rng, opts.threads);
+
+    let results: Vec<Result<(Vec<f64>, Vec<usize>)>> = thread::scope(|scope| {
+        let mut threads = vec![];
+
+        for (i, thread_seed) in thread_seeds.iter().enumerate() {
+            let limit = samples / opts.threads + usize::from(i < samples % opts.threads);
+            let scorer = &scorer;
+            let mut rng = get_rng(*thread_seed);
+
+            threads.push(scope.spawn(move || {
+                let mut scores_scoped = Vec::<f64>::new();
+                let mut lengths_scoped = Vec::<usize>::new();
+
                 for _ in 0..limit {
-                    let new_seq = shuffle_and_randomize_sequence(&target_clone);
-
-                    let mut aligner =
-                        SimpleLocalAligner::<Protein>::from_seqs(&query_clone, &new_seq).unwrap();
-
-                    scores_scoped.push(
-                        aligner
-                            .perform_alignment(del, ins, &matrix_clone, None)
-                            .unwrap()
-                            .alignment
-                            .f,
-                    );
+                    let new_seq = opts.null_model.generate(target, &mut rng);
+
+                    scores_scoped.push(scorer(&new_seq)?);
                     lengths_scoped.push(new_seq.len());
                 }
 
-                (scores_scoped, lengths_scoped)
-            }
-        }))
-    }
+                Ok((scores_scoped, lengths_scoped))
+            }))
+        }
+
+        threads.into_iter().map(|t| t.join().unwrap()).collect()
+    });
 
-    for t in threads {
-        let mut items = t.join().unwrap();
+    for items in results {
+        let mut items = items?;
         scores.append(&mut items.0);
         lengths.append(&mut items.1);
     }
 
     debug!("Calculating distribution params");
 
-    let p_value = match calculate_distribution_params(
-        query.len(),
-        &Array1::from_vec(lengths),
-        &Array1::from_vec(scores),
-    ) {
-        Ok(params) => params,
-        Err(err) => return Err(err),
-    }
-    .get_p_value(query.len(), target.len(), initial_score);
+    let lengths = Array1::from_vec(lengths);
+    let scores = Array1::from_vec(scores);
 
-    Ok(p_value)
-}
+    let (params, report) = fit_distribution_params(query_length, &lengths, &scores)?;
 
-fn shuffle_and_randomize_sequence(sequence: &[Protein]) -> Vec<Protein> {
-    let mut thread_rng = thread_rng();
+    debug!("{:?}", report);
 
-    let lock = thread_rng.gen_range(0..7);
+    let confidence = if opts.bootstrap > 0 {
+        bootstrap_distribution_params(
+            query_length,
+            &lengths,
+            &scores,
+            target.len(),
+            initial_score,
+            opts,
+            &mut rng,
+        )
+        .ok()
+    } else {
+        None
+    };
 
-    let target_bytes = &sequence[..sequence.len() - lock];
-    let mut target_vec = Vec::<Protein>::from(target_bytes);
+    Ok(Significance {
+        p_value: params.get_p_value(query_length, target.len(), initial_score),
+        params,
+        seed: Some(seed),
+        report: Some(report),
+        confidence,
+    })
+}
 
-    target_vec.shuffle(&mut thread_rng);
+// The matrix shuffled sequences are scored with. Null models keep the target
+// composition, so one rescaling fits every shuffle
+pub fn get_scoring_matrix<T>(
+    query: &[T],
+    target: &[T],
+    matrix: &Array2<f64>,
+    opts: &PValueOptions,
+) -> Result<Array2<f64>>
+where
+    T: BioData + Into<usize> + Copy,
+{
+    match &opts.composition_adjustment {
+        Some(background) => rescale_matrix(
+            matrix,
+            background,
+            &get_composition(query),
+            &get_composition(target),
+        ),
+        None => Ok(matrix.clone()),
+    }
+}
 
-    target_vec
+pub fn calculate_p_value<T>(
+    query: &[T],
+    target: &[T],
+    initial_score: f64,
+    del: f64,
+    ext: f64,
+    matrix: &Array2<f64>,
+    opts: &PValueOptions,
+) -> Result<Significance>
+where
+    T: BioData + Into<usize> + Copy + Eq + Send + Sync,
+{
+    let matrix = get_scoring_matrix(query, target, matrix, opts)?;
+
+    estimate_p_value(
+        query.len(),
+        target,
+        initial_score,
+        |new_seq| {
+            Ok(SimpleLocalAligner::<T>::from_seqs(query, new_seq)?
+                .perform_alignment(del, ext, &matrix, None)?
+                .alignment
+                .f)
+        },
+        opts,
+    )
+}
+
+pub fn calculate_significance<T>(
+    query: &[T],
+    target: &[T],
+    initial_score: f64,
+    del: f64,
+    ext: f64,
+    matrix: &Array2<f64>,
+    opts: &PValueOptions,
+) -> Result<Significance>
+where
+    T: BioData + Into<usize> + Copy + Eq + Send + Sync,
+{
+    match get_gapped_params(matrix, del, ext) {
+        Some(params) => Ok(Significance::from_params(
+            params,
+            query.len(),
+            target.len(),
+            initial_score,
+        )),
+        None => calculate_p_value(query, target, initial_score, del, ext, matrix, opts),
+    }
+}
+
+pub fn calculate_pwm_p_value<T>(
+    window: &[T],
+    initial_score: f64,
+    del: f64,
+    ext: f64,
+    matrix: &Array2<f64>,
+    opts: &PValueOptions,
+) -> Result<Significance>
+where
+    T: BioData + Into<usize> + Copy + Eq + Send + Sync,
+{
+    estimate_p_value(
+        matrix.len_of(Axis(1)),
+        window,
+        initial_score,
+        |new_seq| {
+            Ok(PWMAligner::<T>::from_seqs(new_seq, &[])?
+                .perform_alignment(del, ext, matrix, None)?
+                .alignment
+                .f)
+        },
+        opts,
+    )
 }
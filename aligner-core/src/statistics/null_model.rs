@@ -0,0 +1,179 @@
+use crate::{Error, Result};
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum NullModel {
+    #[default]
+    Shuffle,
+    KLet(usize),
+    Window(usize),
+    Markov(usize),
+}
+
+impl FromStr for NullModel {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<NullModel> {
+        let s = s.to_lowercase();
+
+        let (name, parameter) = match s.split_once(':') {
+            Some((name, parameter)) => match parameter.parse::<usize>() {
+                Ok(parameter) => (name, Some(parameter)),
+                Err(_) => return Err(Error::ParseError),
+            },
+            None => (s.as_str(), None),
+        };
+
+        match (name, parameter) {
+            ("shuffle", None) => Ok(NullModel::Shuffle),
+            ("klet", Some(k)) if k > 0 => Ok(NullModel::KLet(k)),
+            ("window", Some(w)) if w > 0 => Ok(NullModel::Window(w)),
+            ("markov", Some(k)) => Ok(NullModel::Markov(k)),
+            _ => Err(Error::ValidationError),
+        }
+    }
+}
+
+impl NullModel {
+    pub fn generate<T, R>(&self, sequence: &[T], rng: &mut R) -> Vec<T>
+    where
+        T: Into<usize> + Copy,
+        R: Rng + ?Sized,
+    {
+        match *self {
+            NullModel::Shuffle => {
+                let mut result = Vec::from(sequence);
+                result.shuffle(rng);
+                result
+            }
+            NullModel::KLet(k) => klet_shuffle(sequence, k, rng),
+            NullModel::Window(w) => {
+                let mut result = Vec::from(sequence);
+                for window in result.chunks_mut(w) {
+                    window.shuffle(rng);
+                }
+                result
+            }
+            NullModel::Markov(k) => markov_sequence(sequence, k, rng),
+        }
+    }
+}
+
+fn key<T: Into<usize> + Copy>(kmer: &[T]) -> Vec<usize> {
+    kmer.iter().map(|elem| (*elem).into()).collect()
+}
+
+// Altschul-Erickson shuffle generalised to k-lets: a random Eulerian path in
+// the multigraph of (k-1)-mers, with the last exit edges of every vertex
+// drawn as a random arborescence rooted at the final (k-1)-mer (Wilson).
+fn klet_shuffle<T, R>(sequence: &[T], k: usize, rng: &mut R) -> Vec<T>
+where
+    T: Into<usize> + Copy,
+    R: Rng + ?Sized,
+{
+    if k <= 1 || sequence.len() <= k {
+        let mut result = Vec::from(sequence);
+        if k <= 1 {
+            result.shuffle(rng);
+        }
+        return result;
+    }
+
+    let mut vertices = HashMap::<Vec<usize>, usize>::new();
+    let ids: Vec<usize> = sequence
+        .windows(k - 1)
+        .map(|kmer| {
+            let id = vertices.len();
+            *vertices.entry(key(kmer)).or_insert(id)
+        })
+        .collect();
+
+    let mut edges: Vec<Vec<(usize, T)>> = vec![vec![]; vertices.len()];
+    for i in 0..ids.len() - 1 {
+        edges[ids[i]].push((ids[i + 1], sequence[i + k - 1]));
+    }
+
+    let start = ids[0];
+    let end = ids[ids.len() - 1];
+
+    let mut in_tree = vec![false; vertices.len()];
+    let mut last = vec![0usize; vertices.len()];
+    in_tree[end] = true;
+
+    for vertex in 0..vertices.len() {
+        let mut u = vertex;
+        while !in_tree[u] {
+            last[u] = rng.gen_range(0..edges[u].len());
+            u = edges[u][last[u]].0;
+        }
+
+        let mut u = vertex;
+        while !in_tree[u] {
+            in_tree[u] = true;
+            u = edges[u][last[u]].0;
+        }
+    }
+
+    for (vertex, vertex_edges) in edges.iter_mut().enumerate() {
+        if vertex_edges.is_empty() {
+            continue;
+        }
+
+        let tail = vertex_edges.len() - 1;
+        if vertex != end {
+            vertex_edges.swap(last[vertex], tail);
+            vertex_edges[..tail].shuffle(rng);
+        } else {
+            vertex_edges.shuffle(rng);
+        }
+    }
+
+    let mut result = Vec::from(&sequence[..k - 1]);
+    let mut positions = vec![0usize; vertices.len()];
+    let mut u = start;
+
+    for _ in 0..ids.len() - 1 {
+        let (next, elem) = edges[u][positions[u]];
+        positions[u] += 1;
+        result.push(elem);
+        u = next;
+    }
+
+    result
+}
+
+fn markov_sequence<T, R>(sequence: &[T], k: usize, rng: &mut R) -> Vec<T>
+where
+    T: Into<usize> + Copy,
+    R: Rng + ?Sized,
+{
+    if sequence.len() <= k {
+        return Vec::from(sequence);
+    }
+
+    let mut transitions = HashMap::<Vec<usize>, Vec<T>>::new();
+    for i in 0..sequence.len() - k {
+        transitions
+            .entry(key(&sequence[i..i + k]))
+            .or_default()
+            .push(sequence[i + k]);
+    }
+
+    let start = rng.gen_range(0..=sequence.len() - k);
+    let mut result = Vec::from(&sequence[start..start + k]);
+
+    while result.len() < sequence.len() {
+        let elem = match transitions.get(&key(&result[result.len() - k..])) {
+            Some(successors) => *successors.choose(rng).unwrap(),
+            None => *sequence.choose(rng).unwrap(),
+        };
+
+        result.push(elem);
+    }
+
+    result
+}
@@ -0,0 +1,333 @@
+use crate::enums::{BioData, Protein, DNA};
+use crate::simple::SimpleLocalAligner;
+use crate::statistics::cache::{get_cache_key, ParamsCache};
+use crate::statistics::composition::{get_composition, get_smoothed_composition, rescale_matrix};
+use crate::statistics::evd::{calculate_q_values, GumbelParams};
+use crate::statistics::karlin::{calculate_ungapped_params, get_gapped_params};
+use crate::statistics::null_model::NullModel;
+use crate::statistics::{
+    estimate_p_value, fit_distribution_params, get_scoring_matrix, ConvergenceReport,
+    DistributionParams, PValueOptions, Significance,
+};
+use crate::{
+    get_blosum62, get_dna_matrix, get_rng, get_robinson_frequencies, AlignerTrait, Result,
+};
+use ndarray::Array1;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+const SEQUENCE: &str = "ATGCGCGATATTTACGCGGGCATATCGCGATTAGCGCATCGATCGGGCTTAAACGCGCATATGC";
+
+// Local alignment scores of shuffled DNA targets against the first half of SEQUENCE
+fn estimate_dna_p_value(seed: u64) -> Result<Significance> {
+    let target = DNA::str_to_vec(SEQUENCE).unwrap();
+    let query = target[..target.len() / 2].to_vec();
+    let matrix = get_dna_matrix(2f64, -3f64);
+
+    let scorer = |target: &[DNA]| {
+        Ok(SimpleLocalAligner::from_seqs(&query, target)?
+            .perform_alignment(5f64, 2f64, &matrix, None)?
+            .alignment
+            .f)
+    };
+
+    let opts = PValueOptions {
+        sequences: 200,
+        threads: 2,
+        seed: Some(seed),
+        ..Default::default()
+    };
+
+    estimate_p_value(query.len(), &target, scorer(&target)?, scorer, &opts)
+}
+
+fn kmer_counts(sequence: &[DNA], k: usize) -> HashMap<String, usize> {
+    let mut result = HashMap::new();
+
+    for kmer in sequence.windows(k) {
+        *result.entry(DNA::vec_to_str(kmer).unwrap()).or_insert(0) += 1;
+    }
+
+    result
+}
+
+#[test]
+fn klet_shuffle_preserves_kmer_counts_test() {
+    let sequence = DNA::str_to_vec(SEQUENCE).unwrap();
+    let mut rng = get_rng(42);
+
+    for k in 1..=3 {
+        let shuffled = NullModel::KLet(k).generate(&sequence, &mut rng);
+
+        assert_eq!(shuffled.len(), sequence.len());
+        assert_eq!(shuffled[..k - 1], sequence[..k - 1]);
+        assert_eq!(kmer_counts(&shuffled, k), kmer_counts(&sequence, k));
+    }
+}
+
+#[test]
+fn window_shuffle_preserves_local_composition_test() {
+    let sequence = DNA::str_to_vec(SEQUENCE).unwrap();
+    let shuffled = NullModel::Window(10).generate(&sequence, &mut get_rng(42));
+
+    for (window, shuffled_window) in sequence.chunks(10).zip(shuffled.chunks(10)) {
+        assert_eq!(kmer_counts(window, 1), kmer_counts(shuffled_window, 1));
+    }
+}
+
+#[test]
+fn markov_sequence_is_seeded_test() {
+    let sequence = DNA::str_to_vec(SEQUENCE).unwrap();
+
+    let first = NullModel::Markov(2).generate(&sequence, &mut get_rng(7));
+    let second = NullModel::Markov(2).generate(&sequence, &mut get_rng(7));
+
+    assert_eq!(first.len(), sequence.len());
+    assert_eq!(first, second);
+}
+
+#[test]
+fn shuffle_is_seeded_test() {
+    let sequence = DNA::str_to_vec(SEQUENCE).unwrap();
+
+    let first = NullModel::Shuffle.generate(&sequence, &mut get_rng(7));
+    let second = NullModel::Shuffle.generate(&sequence, &mut get_rng(7));
+
+    assert_eq!(first, second);
+}
+
+#[test]
+fn null_model_from_str_test() {
+    assert_eq!(NullModel::from_str("shuffle").unwrap(), NullModel::Shuffle);
+    assert_eq!(NullModel::from_str("klet:2").unwrap(), NullModel::KLet(2));
+    assert_eq!(
+        NullModel::from_str("Window:50").unwrap(),
+        NullModel::Window(50)
+    );
+    assert_eq!(
+        NullModel::from_str("markov:0").unwrap(),
+        NullModel::Markov(0)
+    );
+    assert!(NullModel::from_str("klet:0").is_err());
+    assert!(NullModel::from_str("window").is_err());
+}
+
+#[test]
+fn blosum62_ungapped_params_test() {
+    let freqs = get_robinson_frequencies();
+    let params = calculate_ungapped_params(&get_blosum62(), &freqs, &freqs).unwrap();
+
+    assert!((params.lambda - 0.3176).abs() < 1e-3);
+    assert!((params.k - 0.134).abs() < 5e-3);
+    assert!((params.h - 0.4012).abs() < 5e-3);
+}
+
+#[test]
+fn blosum62_gapped_params_test() {
+    let params = get_gapped_params(&get_blosum62(), 12f64, 1f64).unwrap();
+
+    assert_eq!(params.lambda, 0.267);
+    assert_eq!(params.k, 0.041);
+    assert!(get_gapped_params(&get_blosum62(), 12f64, 3f64).is_none());
+}
+
+#[test]
+fn e_value_and_bit_score_test() {
+    let params = get_gapped_params(&get_blosum62(), 12f64, 1f64).unwrap();
+
+    let search_space = params.get_search_space(250, 1_000_000, 3000);
+    let e_value = params.get_e_value(search_space, 60f64);
+
+    assert!(search_space < 250f64 * 1_000_000f64);
+    assert!((params.get_bit_score(60f64) - 27.72).abs() < 0.01);
+    assert!(e_value > 0f64 && e_value < 1f64);
+}
+
+#[test]
+fn params_cache_interpolation_test() {
+    let mut cache = ParamsCache::default();
+    let target = DNA::str_to_vec(SEQUENCE).unwrap();
+    let opts = PValueOptions::default();
+    let key = get_cache_key(&get_blosum62(), 11f64, 1f64, &target, &opts);
+
+    let other_opts = PValueOptions {
+        null_model: NullModel::KLet(2),
+        ..Default::default()
+    };
+    assert_ne!(
+        key,
+        get_cache_key(&get_blosum62(), 11f64, 1f64, &target, &other_opts)
+    );
+    let other_opts = PValueOptions {
+        seed: Some(1),
+        threads: 1,
+        ..Default::default()
+    };
+    assert_eq!(
+        key,
+        get_cache_key(&get_blosum62(), 11f64, 1f64, &target, &other_opts)
+    );
+
+    let params = |k: f64, lambda: f64| DistributionParams { k, lambda, h: 1f64 };
+    cache.insert(&key, 128, params(0.1, 0.2));
+    cache.insert(&key, 512, params(0.4, 0.4));
+
+    assert_eq!(cache.get(&key, 130).unwrap().lambda, 0.2);
+    assert!((cache.get(&key, 256).unwrap().lambda - 0.3).abs() < 1e-9);
+    assert!((cache.get(&key, 256).unwrap().k - 0.2).abs() < 1e-9);
+    assert!(cache.get(&key, 1024).is_none());
+    assert!(cache.get("unknown", 256).is_none());
+
+    let restored: ParamsCache =
+        serde_json::from_str(&serde_json::to_string(&cache).unwrap()).unwrap();
+    assert_eq!(restored.get(&key, 512).unwrap().k, 0.4);
+}
+
+#[test]
+fn gumbel_fit_test() {
+    use rand::Rng;
+
+    let mut rng = get_rng(11);
+    let scores: Vec<f64> = (0..5000)
+        .map(|_| 20f64 - 4f64 * (-rng.gen::<f64>().ln()).ln())
+        .collect();
+
+    let params = GumbelParams::fit(&scores).unwrap();
+
+    assert!((params.mu - 20f64).abs() < 0.3);
+    assert!((params.beta - 4f64).abs() < 0.2);
+    assert!((params.get_p_value(params.mu) - (1f64 - (-1f64).exp())).abs() < 1e-12);
+}
+
+#[test]
+fn benjamini_hochberg_test() {
+    let q_values = calculate_q_values(&[0.01, 0.04, 0.03, 0.5]);
+
+    assert_eq!(
+        q_values,
+        vec![0.04, 0.04 * 4f64 / 3f64, 0.04 * 4f64 / 3f64, 0.5]
+    );
+}
+
+#[test]
+fn significance_reliability_test() {
+    let params = get_gapped_params(&get_blosum62(), 12f64, 1f64).unwrap();
+    let mut significance = Significance::from_params(params, 250, 300, 45f64);
+
+    assert!(significance.is_reliable());
+
+    significance.report = Some(ConvergenceReport {
+        iterations: 10001,
+        converged: false,
+        log_likelihood: f64::NAN,
+        scores_kept: 500,
+        scores_total: 500,
+    });
+
+    assert!(!significance.is_reliable());
+}
+
+#[test]
+fn rescale_matrix_test() {
+    let matrix = get_blosum62();
+    let background = get_robinson_frequencies();
+
+    let rescaled = rescale_matrix(&matrix, &background, &background, &background).unwrap();
+    assert_eq!(rescaled, matrix);
+
+    let query = Protein::str_to_vec("WWWWCCCCWWWWCCCCHHHHMMMMWWWWCCCC").unwrap();
+    let composition = get_composition(&query);
+    assert!((composition.sum() - 1f64).abs() < 1e-12);
+
+    let rescaled = rescale_matrix(&matrix, &background, &composition, &background).unwrap();
+    let lambda = |matrix| {
+        calculate_ungapped_params(matrix, &composition, &background)
+            .unwrap()
+            .lambda
+    };
+    let standard = calculate_ungapped_params(&matrix, &background, &background)
+        .unwrap()
+        .lambda;
+
+    assert!((lambda(&rescaled) - standard).abs() < (lambda(&matrix) - standard).abs());
+
+    let target = Protein::str_to_vec("ARNDCQEGHILKMFPSTWYV").unwrap();
+    let opts = PValueOptions::default();
+    assert_eq!(
+        get_scoring_matrix(&query, &target, &matrix, &opts).unwrap(),
+        matrix
+    );
+
+    let opts = PValueOptions {
+        composition_adjustment: Some(background.clone()),
+        ..Default::default()
+    };
+    assert_eq!(
+        get_scoring_matrix(&query, &target, &matrix, &opts).unwrap(),
+        rescale_matrix(
+            &matrix,
+            &background,
+            &composition,
+            &get_composition(&target)
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn smoothed_composition_test() {
+    let sequence = DNA::str_to_vec("AAAATTTT").unwrap();
+    let composition = get_smoothed_composition(&sequence, 1f64);
+
+    assert_eq!(
+        composition.to_vec(),
+        vec![5f64 / 12f64, 5f64 / 12f64, 1f64 / 12f64, 1f64 / 12f64]
+    );
+    assert_eq!(
+        get_smoothed_composition::<DNA>(&[], 1f64).to_vec(),
+        vec![0.25; 4]
+    );
+}
+
+#[test]
+fn distribution_params_fit_test() {
+    use rand::Rng;
+
+    let (lambda, k) = (0.3f64, 0.1f64);
+    let (query_length, target_length) = (300usize, 300usize);
+    let space = (query_length * target_length) as f64;
+
+    let mut rng = get_rng(7);
+    let scores: Array1<f64> = (0..5000)
+        .map(|_| ((k * space).ln() - (-rng.gen::<f64>().ln()).ln()) / lambda)
+        .collect();
+    let target_lengths = Array1::from_elem(scores.len(), target_length);
+
+    let (params, report) = fit_distribution_params(query_length, &target_lengths, &scores).unwrap();
+
+    assert!(report.converged);
+    assert_eq!(report.scores_kept, report.scores_total);
+    assert!((params.lambda - lambda).abs() < 0.01);
+    assert!((params.k - k).abs() < 0.01);
+}
+
+#[test]
+fn dna_p_value_test() {
+    let significance = estimate_dna_p_value(1).unwrap();
+
+    assert_eq!(significance.seed, Some(1));
+    assert!((0f64..=1f64).contains(&significance.p_value));
+    assert!(significance.params.lambda > 0f64 && significance.params.k > 0f64);
+}
+
+#[test]
+fn p_value_is_seeded_test() {
+    let (first, second) = (
+        estimate_dna_p_value(3).unwrap(),
+        estimate_dna_p_value(3).unwrap(),
+    );
+
+    assert_eq!(first.p_value, second.p_value);
+    assert_eq!(first.params.lambda, second.params.lambda);
+    assert_eq!(first.params.k, second.params.k);
+}
@@ -0,0 +1,251 @@
+use crate::enums::Direction;
+use crate::{BioData, Error, Result};
+use ndarray::Array2;
+use ndarray_stats::QuantileExt;
+use std::collections::HashMap;
+
+#[cfg(test)]
+mod test;
+
+const WHITE: [u8; 3] = [255, 255, 255];
+const HEAT: [u8; 3] = [33, 102, 172];
+const PATH: [u8; 3] = [215, 48, 39];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DotPlotMode {
+    // Dots where a k-mer of the query occurs in the target
+    Words(usize),
+    // Dots where the ungapped score of a diagonal window reaches the threshold
+    Window { size: usize, threshold: f64 },
+}
+
+// Cell intensities in [0, 1], rows follow the target and columns the query
+// like in the DP matrices; path cells are drawn on top
+#[derive(Debug, Clone)]
+pub struct Plot {
+    pub values: Array2<f64>,
+    pub path: Vec<(usize, usize)>,
+}
+
+impl Plot {
+    pub fn dot_plot<T: BioData + Into<usize> + Copy + Eq>(
+        query: &[T],
+        target: &[T],
+        matrix: &Array2<f64>,
+        mode: DotPlotMode,
+    ) -> Result<Plot> {
+        let mut values = Array2::<f64>::zeros((target.len(), query.len()));
+
+        match mode {
+            DotPlotMode::Words(k) => {
+                if k == 0 {
+                    return Err(Error::ValidationError);
+                }
+
+                let mut words = HashMap::<Vec<usize>, Vec<usize>>::new();
+                for (x, word) in query.windows(k).enumerate() {
+                    words.entry(key(word)).or_default().push(x);
+                }
+
+                for (y, word) in target.windows(k).enumerate() {
+                    for x in words.get(&key(word)).into_iter().flatten() {
+                        for i in 0..k {
+                            values[[y + i, x + i]] = 1f64;
+                        }
+                    }
+                }
+            }
+            DotPlotMode::Window { size, threshold } => {
+                if size == 0 {
+                    return Err(Error::ValidationError);
+                }
+
+                let (rows, columns) = values.dim();
+                let mut scores = Array2::<f64>::zeros((rows, columns));
+
+                for ((y, x), value) in scores.indexed_iter_mut() {
+                    if y + size > rows || x + size > columns {
+                        continue;
+                    }
+
+                    *value = (0..size)
+                        .map(|i| matrix[[target[y + i].into(), query[x + i].into()]])
+                        .sum::<f64>();
+                }
+
+                let max = scores.iter().cloned().fold(threshold, f64::max);
+                for ((y, x), score) in scores.indexed_iter() {
+                    if y + size > rows || x + size > columns || *score < threshold {
+                        continue;
+                    }
+
+                    // Windows are drawn at their centre
+                    let intensity = match max > threshold {
+                        true => (score - threshold) / (max - threshold),
+                        false => 1f64,
+                    };
+                    let cell = &mut values[[y + size / 2, x + size / 2]];
+                    *cell = cell.max(intensity.max(0.1));
+                }
+            }
+        }
+
+        Ok(Plot {
+            values,
+            path: vec![],
+        })
+    }
+
+    // Heatmap of a DP score matrix with the traceback followed from end, or from
+    // the best scoring cell as the local aligners do
+    pub fn heatmap(
+        alignment_matrix: &Array2<f64>,
+        direction_matrix: &Array2<Direction>,
+        end: Option<(usize, usize)>,
+    ) -> Result<Plot> {
+        if alignment_matrix.is_empty() {
+            return Err(Error::ResultIsEmpty);
+        }
+
+        let min = *alignment_matrix.min_skipnan();
+        let max = *alignment_matrix.max_skipnan();
+        let values = alignment_matrix.mapv(|score| match max > min {
+            true => (score - min) / (max - min),
+            false => 0f64,
+        });
+
+        let mut path = vec![];
+
+        if direction_matrix.dim() == alignment_matrix.dim() {
+            let (mut y, mut x) = match end {
+                Some(end) => end,
+                None => alignment_matrix.argmax_skipnan().unwrap(),
+            };
+
+            if y >= alignment_matrix.nrows() || x >= alignment_matrix.ncols() {
+                return Err(Error::ValidationError);
+            }
+
+            loop {
+                path.push((y, x));
+
+                match direction_matrix[[y, x]] {
+                    Direction::Top if y > 0 => y -= 1,
+                    Direction::Left if x > 0 => x -= 1,
+                    Direction::Diagonal if y > 0 && x > 0 => {
+                        y -= 1;
+                        x -= 1;
+                    }
+                    _ => break,
+                }
+            }
+
+            path.reverse();
+        }
+
+        Ok(Plot { values, path })
+    }
+
+    pub fn to_svg(&self, cell: f64) -> String {
+        let (rows, columns) = self.values.dim();
+        let (width, height) = (cell * columns as f64, cell * rows as f64);
+
+        let mut result = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\">\n",
+            width, height, width, height
+        );
+        result += &format!(
+            "<rect width=\"{}\" height=\"{}\" fill=\"{}\"/>\n",
+            width,
+            height,
+            to_hex(WHITE)
+        );
+
+        for ((y, x), value) in self.values.indexed_iter() {
+            if *value <= 0f64 {
+                continue;
+            }
+
+            result += &format!(
+                "<rect x=\"{}\" y=\"{}\" width=\"{c}\" height=\"{c}\" fill=\"{}\"/>\n",
+                x as f64 * cell,
+                y as f64 * cell,
+                to_hex(blend(*value)),
+                c = cell
+            );
+        }
+
+        if !self.path.is_empty() {
+            let points: Vec<String> = self
+                .path
+                .iter()
+                .map(|(y, x)| format!("{},{}", (*x as f64 + 0.5) * cell, (*y as f64 + 0.5) * cell))
+                .collect();
+
+            result += &format!(
+                "<polyline points=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"{}\"/>\n",
+                points.join(" "),
+                to_hex(PATH),
+                (cell / 2f64).max(1f64)
+            );
+        }
+
+        result += "</svg>\n";
+
+        result
+    }
+
+    pub fn to_png(&self, cell: usize) -> Result<Vec<u8>> {
+        let (rows, columns) = self.values.dim();
+        let (width, height) = (columns * cell.max(1), rows * cell.max(1));
+
+        if width == 0 || height == 0 {
+            return Err(Error::ResultIsEmpty);
+        }
+
+        let mut colors = self.values.mapv(blend);
+        for (y, x) in self.path.iter() {
+            colors[[*y, *x]] = PATH;
+        }
+
+        let mut pixels = Vec::with_capacity(width * height * 3);
+        for y in 0..height {
+            for x in 0..width {
+                pixels.extend_from_slice(&colors[[y / cell.max(1), x / cell.max(1)]]);
+            }
+        }
+
+        let mut result = vec![];
+        let mut encoder = png::Encoder::new(&mut result, width as u32, height as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        let written = encoder
+            .write_header()
+            .and_then(|mut writer| writer.write_image_data(&pixels));
+
+        match written {
+            Ok(_) => Ok(result),
+            Err(_) => Err(Error::IOError),
+        }
+    }
+}
+
+fn key<T: Into<usize> + Copy>(word: &[T]) -> Vec<usize> {
+    word.iter().map(|elem| (*elem).into()).collect()
+}
+
+fn blend(value: f64) -> [u8; 3] {
+    let t = value.clamp(0f64, 1f64);
+    let mut color = [0u8; 3];
+
+    for (i, elem) in color.iter_mut().enumerate() {
+        *elem = (WHITE[i] as f64 + (HEAT[i] as f64 - WHITE[i] as f64) * t).round() as u8;
+    }
+
+    color
+}
+
+fn to_hex(color: [u8; 3]) -> String {
+    format!("#{:02x}{:02x}{:02x}", color[0], color[1], color[2])
+}
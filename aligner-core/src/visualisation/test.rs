@@ -0,0 +1,56 @@
+use crate::enums::{BioData, DNA};
+use crate::simple::SimpleLocalAligner;
+use crate::visualisation::{DotPlotMode, Plot};
+use crate::{get_dna_matrix, AlignerTrait};
+
+const PNG_SIGNATURE: [u8; 8] = [137, 80, 78, 71, 13, 10, 26, 10];
+
+#[test]
+fn word_dot_plot_test() {
+    let query = DNA::str_to_vec("ATGCATGC").unwrap();
+    let target = DNA::str_to_vec("CATG").unwrap();
+    let matrix = get_dna_matrix(1f64, -1f64);
+
+    let plot = Plot::dot_plot(&query, &target, &matrix, DotPlotMode::Words(3)).unwrap();
+
+    assert_eq!(plot.values.dim(), (4, 8));
+    // CAT at target 0 matches query 3, ATG at target 1 matches query 0 and 4
+    assert_eq!(plot.values[[0, 3]], 1f64);
+    assert_eq!(plot.values[[1, 0]], 1f64);
+    assert_eq!(plot.values[[3, 6]], 1f64);
+    assert_eq!(plot.values[[0, 0]], 0f64);
+
+    let window = DotPlotMode::Window {
+        size: 3,
+        threshold: 3f64,
+    };
+    let plot = Plot::dot_plot(&query, &target, &matrix, window).unwrap();
+    assert!(plot.values[[1, 4]] > 0f64);
+    assert_eq!(plot.values[[1, 1]], 0f64);
+
+    assert!(Plot::dot_plot(&query, &target, &matrix, DotPlotMode::Words(0)).is_err());
+}
+
+#[test]
+fn heatmap_traceback_test() {
+    let matrix = get_dna_matrix(2f64, -3f64);
+    let result = SimpleLocalAligner::<DNA>::from_str_seqs("GGATGCAT", "TTATGCATT")
+        .unwrap()
+        .perform_alignment(5f64, 2f64, &matrix, None)
+        .unwrap();
+
+    let plot = Plot::heatmap(&result.alignment_matrix, &result.direction_matrix, None).unwrap();
+
+    assert_eq!(plot.values.dim(), result.alignment_matrix.dim());
+    assert!(plot.path.len() > 1);
+    assert!(plot
+        .path
+        .windows(2)
+        .all(|step| step[1].0 >= step[0].0 && step[1].1 >= step[0].1));
+
+    let svg = plot.to_svg(4f64);
+    assert!(svg.starts_with("<svg") && svg.contains("<polyline"));
+
+    let png = plot.to_png(2).unwrap();
+    assert_eq!(png[..8], PNG_SIGNATURE);
+}
@@ -10,6 +10,10 @@ pub struct Record {
     pub z_value: f64,
     pub left_coord: usize,
     pub right_coord: usize,
+    #[serde(default)]
+    pub p_value: Option<f64>,
+    #[serde(default)]
+    pub q_value: Option<f64>,
 }
 
 pub fn read_csv(path: &Path) -> Result<HashMap<String, Vec<Record>>, impl Error> {
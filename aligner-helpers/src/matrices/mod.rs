@@ -22,6 +22,10 @@ pub fn transform_matrix(
     r_squared: f64,
     frequencies: &Array1<f64>,
 ) -> Result<Array2<f64>> {
+    if frequencies.len() != matrix.len_of(Axis(0)) {
+        return Err(WrongMatrixSpecified);
+    }
+
     let f = Array1::<f64>::from_shape_fn(matrix.len_of(Axis(1)), |_| {
         1f64 / matrix.len_of(Axis(1)) as f64
     });
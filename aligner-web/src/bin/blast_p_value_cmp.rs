@@ -1,8 +1,12 @@
-use aligner_core::enums::Protein;
-use aligner_core::get_blosum62;
+use aligner_core::enums::{BioData, Protein};
 use aligner_core::simple::SimpleLocalAligner;
-use aligner_core::statistics::calculate_p_value;
-use aligner_web::repository::get_connection;
+use aligner_core::statistics::cache::{get_cache_key, ParamsCache};
+use aligner_core::statistics::karlin::get_gapped_params;
+use aligner_core::statistics::{calculate_p_value, PValueOptions, Significance};
+use aligner_core::{get_blosum62, AlignerTrait};
+use aligner_web::repository::{get_connection, get_seed};
+use std::env;
+use std::path::PathBuf;
 
 extern crate pretty_env_logger;
 #[macro_use]
@@ -17,12 +21,18 @@ fn main() {
 
     let matrix = get_blosum62();
 
+    let cache_path = PathBuf::from(
+        env::var("PARAMS_CACHE_PATH").unwrap_or_else(|_| String::from("params_cache.json")),
+    );
+    let mut cache = ParamsCache::load(&cache_path).unwrap();
+
+    let seed = get_seed();
+    info!("Calculating p-values with seed {}", seed);
+
     for seqs in seqs_vec.iter() {
         debug!("Calculating p-value for task with id {}", seqs.0);
 
-        let query = Protein::string_to_protein_vec(&match conn
-            .get_sequence_by_identifier(seqs.1.clone())
-        {
+        let query = Protein::str_to_vec(&match conn.get_sequence_by_identifier(seqs.1.clone()) {
             Ok(query) => query,
             Err(_) => {
                 debug!("Query sequence not found. Skipping...");
@@ -30,9 +40,7 @@ fn main() {
             }
         })
         .unwrap();
-        let target = Protein::string_to_protein_vec(&match conn
-            .get_sequence_by_identifier(seqs.2.clone())
-        {
+        let target = Protein::str_to_vec(&match conn.get_sequence_by_identifier(seqs.2.clone()) {
             Ok(target) => target,
             Err(_) => {
                 debug!("Target sequence not found. Skipping...");
@@ -41,18 +49,66 @@ fn main() {
         })
         .unwrap();
 
-        let mut aligner = SimpleLocalAligner::from_protein_seqs(&query, &target).unwrap();
+        let mut aligner = SimpleLocalAligner::from_seqs(&query, &target).unwrap();
 
         let result = aligner
             .perform_alignment(11f64, 1f64, &matrix, None)
             .unwrap();
 
-        let p_value =
-            calculate_p_value(query, target, result.alignment.f, 11f64, 1f64, &matrix).unwrap();
+        let opts = PValueOptions {
+            seed: Some(seed),
+            ..Default::default()
+        };
+
+        let key = get_cache_key(&matrix, 11f64, 1f64, &target, &opts);
+
+        let significance = match cache.get(&key, query.len()) {
+            Some(params) => {
+                Significance::from_params(params, query.len(), target.len(), result.alignment.f)
+            }
+            None => {
+                let significance = calculate_p_value(
+                    &query,
+                    &target,
+                    result.alignment.f,
+                    11f64,
+                    1f64,
+                    &matrix,
+                    &opts,
+                )
+                .unwrap();
+
+                if significance.is_reliable() {
+                    cache.insert(&key, query.len(), significance.params.clone());
+                    cache.save(&cache_path).unwrap();
+                }
+
+                significance
+            }
+        };
+
+        if !significance.is_reliable() {
+            warn!(
+                "Unreliable p-value for task with id {}: {:?}",
+                seqs.0, significance.report
+            );
+        }
+
+        let p_value = significance.p_value;
 
         debug!("Calculated p-value {} for task with id {}", p_value, seqs.0);
 
-        match conn.add_cmp_p_value_by_id(p_value, seqs.0) {
+        // The analytic value is only printed, the estimated one is compared with BLAST
+        if let Some(params) = get_gapped_params(&matrix, 11f64, 1f64) {
+            let analytic =
+                Significance::from_params(params, query.len(), target.len(), result.alignment.f);
+            info!(
+                "P-values for task with id {}: estimated {}, analytic {}",
+                seqs.0, p_value, analytic.p_value
+            );
+        }
+
+        match conn.add_cmp_p_value_by_id(p_value, significance.seed, seqs.0) {
             Ok(_) => debug!("Successfully written p_value to DB"),
             Err(err) => debug!("{} happened, skipping", err),
         };
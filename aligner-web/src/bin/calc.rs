@@ -0,0 +1,118 @@
+use aligner_core::enums::{BioData, Protein};
+use aligner_core::simple::SimpleLocalAligner;
+use aligner_core::statistics::cache::{get_cache_key, ParamsCache};
+use aligner_core::statistics::{
+    calculate_p_value, get_scoring_matrix, PValueOptions, Significance,
+};
+use aligner_core::{get_robinson_frequencies, AlignerTrait};
+use aligner_web::repository::{get_connection, get_seed};
+use std::env;
+use std::path::PathBuf;
+
+extern crate pretty_env_logger;
+#[macro_use]
+extern crate log;
+
+const BOOTSTRAP: usize = 100;
+
+fn main() {
+    pretty_env_logger::init();
+
+    let mut conn = get_connection(false);
+
+    let ids = conn.get_ids_with_null_p_value().unwrap();
+
+    let cache_path = PathBuf::from(
+        env::var("PARAMS_CACHE_PATH").unwrap_or_else(|_| String::from("params_cache.json")),
+    );
+    let mut cache = ParamsCache::load(&cache_path).unwrap();
+
+    let seed = get_seed();
+    info!("Calculating p-values with seed {}", seed);
+
+    // Set COMPOSITION_ADJUSTMENT to score pairs and their shuffles with the
+    // matrix rescaled to the pair's composition
+    let composition_adjustment = env::var("COMPOSITION_ADJUSTMENT").is_ok();
+
+    for id in ids.iter() {
+        debug!("Calculating p-value for task with id {}", id);
+
+        let subtask = conn.get_result_matrix_by_task_id(*id).unwrap();
+
+        let query = Protein::str_to_vec(&subtask.query_sequence).unwrap();
+        let target = Protein::str_to_vec(&subtask.target_sequence).unwrap();
+
+        let opts = PValueOptions {
+            bootstrap: BOOTSTRAP,
+            seed: Some(seed),
+            composition_adjustment: composition_adjustment.then(get_robinson_frequencies),
+            ..Default::default()
+        };
+
+        // The stored score comes from the original matrix, so the pair is realigned
+        let f_value = match &opts.composition_adjustment {
+            Some(_) => {
+                let matrix = get_scoring_matrix(&query, &target, &subtask.matrix, &opts).unwrap();
+
+                SimpleLocalAligner::<Protein>::from_seqs(&query, &target)
+                    .unwrap()
+                    .perform_alignment(subtask.del_value, subtask.ext_value, &matrix, None)
+                    .unwrap()
+                    .alignment
+                    .f
+            }
+            None => subtask.f_value,
+        };
+
+        let key = get_cache_key(
+            &subtask.matrix,
+            subtask.del_value,
+            subtask.ext_value,
+            &target,
+            &opts,
+        );
+
+        let significance = match cache.get(&key, query.len()) {
+            Some(params) => Significance::from_params(params, query.len(), target.len(), f_value),
+            None => {
+                let significance = calculate_p_value(
+                    &query,
+                    &target,
+                    f_value,
+                    subtask.del_value,
+                    subtask.ext_value,
+                    &subtask.matrix,
+                    &opts,
+                )
+                .unwrap();
+
+                if significance.is_reliable() {
+                    cache.insert(&key, query.len(), significance.params.clone());
+                    cache.save(&cache_path).unwrap();
+                }
+
+                significance
+            }
+        };
+
+        if !significance.is_reliable() {
+            warn!(
+                "Unreliable p-value for task with id {}: {:?}, {:?}",
+                id, significance.report, significance.confidence
+            );
+        }
+
+        debug!(
+            "Calculated p-value {} for task with id {}",
+            significance.p_value, id
+        );
+
+        conn.add_p_value_by_id(
+            significance.p_value,
+            significance.is_reliable(),
+            significance.seed,
+            *id,
+        )
+        .unwrap();
+    }
+}
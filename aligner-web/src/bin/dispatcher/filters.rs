@@ -3,7 +3,7 @@ use warp::{http::Method, Filter};
 
 // API combined filter
 pub fn get_api() -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
-    check_health().or(validate()).or(progress())
+    check_health().or(validate()).or(progress()).or(result())
 }
 
 // GET health/check with no params
@@ -47,3 +47,17 @@ pub fn progress() -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejec
         .and_then(handlers::progress)
         .with(cors_progress)
 }
+
+// GET /result/{hash} with the text report of a finished alignment
+pub fn result() -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    let cors_result = warp::cors()
+        .allow_any_origin()
+        .allow_header("content-type")
+        .allow_methods(&[Method::GET]);
+
+    warp::get()
+        .and(warp::path("result"))
+        .and(warp::path::param::<String>())
+        .and_then(handlers::result)
+        .with(cors_result)
+}
@@ -1,9 +1,13 @@
-use aligner::repository::get_connection;
 use aligner::matrices::get_population;
+use aligner::repository::{get_connection, get_seed};
 use aligner::web::models::{
-    AlignJob, AlignJobRequest, EmptySuccessfulResponseWithHashes, ErroneousResponse, HealthCheck,
-    HealthCheckUnit, ProgressEventResponse,
+    AlignJob, AlignJobRequest, AlignmentReportResponse, EmptySuccessfulResponseWithHashes,
+    ErroneousResponse, HealthCheck, HealthCheckUnit, ProgressEventResponse,
 };
+use aligner_core::alignment::report::{format_pairwise_report, ReportOptions};
+use aligner_core::alignment::Alignment;
+use aligner_core::enums::{BioData, Protein};
+use aligner_core::get_rng;
 use futures::StreamExt;
 use ndarray::arr1;
 use rdkafka::{
@@ -106,6 +110,9 @@ pub async fn validate(job: AlignJobRequest) -> Result<impl warp::Reply, Infallib
 
     let mut conn = get_connection(false);
 
+    let seed = get_seed();
+    info!("Spawning alignment tasks with seed {}.", seed);
+
     for (query, target) in sequence_pairs.iter() {
         let hash = match conn.insert_align_task(
             &job,
@@ -113,6 +120,7 @@ pub async fn validate(job: AlignJobRequest) -> Result<impl warp::Reply, Infallib
             String::from_utf8(query.seq.clone()).unwrap(),
             String::from_utf8(target.head.clone()).unwrap(),
             String::from_utf8(target.seq.clone()).unwrap(),
+            seed,
         ) {
             Ok(hash) => hash,
             Err(err) => {
@@ -127,6 +135,7 @@ pub async fn validate(job: AlignJobRequest) -> Result<impl warp::Reply, Infallib
             (seqs[0].seq.clone(), seqs[1].seq.clone()),
             &job,
             hash.clone(),
+            seed,
         );
     }
 
@@ -180,6 +189,78 @@ pub async fn progress(hashes: Vec<String>) -> Result<impl warp::Reply, Infallibl
     Ok(warp::sse::reply(event_stream))
 }
 
+// GET /result/{hash} handler
+pub async fn result(hash: String) -> Result<impl warp::Reply, Infallible> {
+    let mut conn = get_connection(false);
+
+    let result = match conn.get_result_by_hash(hash.clone()) {
+        Ok(result) => result,
+        Err(err) => {
+            debug!(
+                "Failed with {}. No result for task with hash {}.",
+                err, hash
+            );
+
+            return Ok(warp::reply::with_status(
+                warp::reply::json(&ErroneousResponse {
+                    message: "Результат выравнивания не найден.".to_owned(),
+                }),
+                StatusCode::NOT_FOUND,
+            ));
+        }
+    };
+
+    let alignment = match (
+        Protein::str_to_vec(&result.result_query_sequence),
+        Protein::str_to_vec(&result.result_target_sequence),
+    ) {
+        (Ok(query), Ok(target)) => {
+            // Older results have no coordinates, their residues are counted from 1
+            let coords = result.coords.unwrap_or_else(|| {
+                let residues = |sequence: &[Protein]| {
+                    sequence
+                        .iter()
+                        .filter(|elem| **elem != Protein::blank())
+                        .count()
+                };
+
+                ((1, residues(&query) + 1), (1, residues(&target) + 1))
+            });
+
+            Alignment::new(query, target, coords, result.f_value)
+        }
+        _ => Err(aligner_core::Error::ParseError),
+    };
+
+    match alignment {
+        Ok(alignment) => Ok(warp::reply::with_status(
+            warp::reply::json(&AlignmentReportResponse {
+                hash,
+                report: format_pairwise_report(
+                    &alignment,
+                    &result.matrix,
+                    result.p_value,
+                    &ReportOptions::default(),
+                ),
+            }),
+            StatusCode::OK,
+        )),
+        Err(err) => {
+            debug!(
+                "Failed with {:?}. Stored alignment for {} is broken.",
+                err, hash
+            );
+
+            Ok(warp::reply::with_status(
+                warp::reply::json(&ErroneousResponse {
+                    message: "Ошибка при обработке результата выравнивания.".to_owned(),
+                }),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ))
+        }
+    }
+}
+
 fn sse_event(percentages: HashMap<String, f64>) -> Result<Event, Infallible> {
     Ok(Event::default()
         .json_data(&ProgressEventResponse {
@@ -190,7 +271,7 @@ fn sse_event(percentages: HashMap<String, f64>) -> Result<Event, Infallible> {
 }
 
 // A function to spawn jobs for alignment in Kafka
-fn spawn_jobs(sequences: (Vec<u8>, Vec<u8>), job: &AlignJobRequest, hash: String) {
+fn spawn_jobs(sequences: (Vec<u8>, Vec<u8>), job: &AlignJobRequest, hash: String, seed: u64) {
     let producer: &BaseProducer = &ClientConfig::new()
         .set("bootstrap.servers", env::var("KAFKA_HOST").unwrap())
         .set("message.timeout.ms", "1000")
@@ -203,6 +284,7 @@ fn spawn_jobs(sequences: (Vec<u8>, Vec<u8>), job: &AlignJobRequest, hash: String
         &mut conn,
         job.dim_value as usize,
         job.matrices_volume_value as usize,
+        &mut get_rng(seed),
     )
     .unwrap();
 
@@ -231,6 +313,7 @@ fn spawn_jobs(sequences: (Vec<u8>, Vec<u8>), job: &AlignJobRequest, hash: String
         kd_value: job.kd_value,
         r_squared_value: job.r_squared_value,
         del_value: job.del_value,
+        ext_value: job.get_ext_value(),
         matrices_volume_value: job.matrices_volume_value,
         hash,
     };
@@ -1,6 +1,8 @@
-use aligner::align::heuristic_aligner::HeuristicPairwiseAlignmentTool;
 use aligner::repository::get_connection;
-use aligner::{align::enums::Protein, web::models::AlignJob};
+use aligner::web::models::AlignJob;
+use aligner_core::enums::{BioData, Protein};
+use aligner_core::heuristic::HeuristicAligner;
+use aligner_core::{AlignerTrait, AlignmentTrait, Heuristics};
 
 use rdkafka::{consumer::CommitMode, message::Message};
 use rdkafka::{
@@ -59,29 +61,50 @@ async fn main() {
                 );
 
                 let job: AlignJob = from_slice(m.payload().unwrap()).unwrap();
-                let mut tool = HeuristicPairwiseAlignmentTool::from_sequences_pair((
-                    Protein::u8_vec_to_protein_vec(&job.sequence_1),
-                    Protein::u8_vec_to_protein_vec(&job.sequence_2),
-                ));
-
-                let (current_f, optimal, sequences) = tool.local_alignment(
-                    job.del_value,
-                    job.del_value,
-                    job.kd_value,
-                    job.r_squared_value,
-                    &job.matrix.unwrap(),
-                    &job.frequences,
-                );
+
+                // The aligner keeps the coordinates of the local alignment
+                let result = Protein::from_u8_vec(&job.sequence_1)
+                    .and_then(|query| {
+                        let target = Protein::from_u8_vec(&job.sequence_2)?;
+                        HeuristicAligner::<Protein>::from_seqs(&query, &target)
+                    })
+                    .and_then(|mut aligner| {
+                        aligner.perform_alignment(
+                            job.del_value,
+                            job.ext_value,
+                            &job.matrix.unwrap(),
+                            Some(Heuristics {
+                                kd: job.kd_value,
+                                r_squared: job.r_squared_value,
+                                frequencies: job.frequences.clone(),
+                            }),
+                        )
+                    });
 
                 consumer.commit_message(&m, CommitMode::Async).unwrap();
 
+                let result = match result {
+                    Ok(result) => result,
+                    Err(err) => {
+                        debug!("Failed with {:?}. Skipping subtask.", err);
+                        continue;
+                    }
+                };
+
+                let optimal = result.matrix.unwrap();
+                let stats = result.alignment.get_stats(
+                    &optimal,
+                    job.sequence_1.len(),
+                    job.sequence_2.len(),
+                );
+
                 if conn
-                    .insert_align_subtask(job.hash.clone(), current_f, &optimal, sequences.clone())
+                    .insert_align_subtask(job.hash.clone(), &result.alignment, &optimal, &stats)
                     .is_err()
                 {
                     conn = get_connection(false);
                     if let Err(err) =
-                        conn.insert_align_subtask(job.hash, current_f, &optimal, sequences)
+                        conn.insert_align_subtask(job.hash, &result.alignment, &optimal, &stats)
                     {
                         debug!("Failed with {}. Error with inserting subtask.", err)
                     }
@@ -1,19 +1,68 @@
 use crate::server::models::AlignJobRequest;
-use aligner_core::enums::Protein;
+use aligner_core::alignment::stats::AlignmentStats;
+use aligner_core::alignment::Alignment;
+use aligner_core::enums::{BioData, Protein};
 use aligner_helpers::matrices::get_threshold;
 use core::panic;
 use mysql::{error::MySqlError, prelude::*};
-use mysql::{Conn, Opts, Result};
+use mysql::{Conn, Opts, Params, Result};
 use ndarray::Array2;
 use ndarray_rand::RandomExt;
 use ndarray_stats::DeviationExt;
 use rand::distributions::Uniform;
+use rand::Rng;
 use std::env;
 use std::str::FromStr;
 
 mod models;
 mod queries;
 
+// Query start and end, target start and end columns
+type CoordsRow = (Option<usize>, Option<usize>, Option<usize>, Option<usize>);
+
+type SubtaskRow = (
+    f64,
+    String,
+    String,
+    String,
+    Option<usize>,
+    Option<usize>,
+    Option<usize>,
+    Option<usize>,
+);
+
+type ResultRow = (
+    f64,
+    String,
+    Option<String>,
+    Option<String>,
+    Option<f64>,
+    Option<usize>,
+    Option<usize>,
+    Option<usize>,
+    Option<usize>,
+);
+
+fn get_coords(row: CoordsRow) -> Option<models::Coords> {
+    match row {
+        (Some(query_start), Some(query_end), Some(target_start), Some(target_end)) => {
+            Some(((query_start, query_end), (target_start, target_end)))
+        }
+        _ => None,
+    }
+}
+
+fn get_sequence_string(sequence: &[Protein]) -> Result<String> {
+    match Protein::vec_to_str(sequence) {
+        Ok(sequence) => Ok(sequence),
+        Err(err) => Err(mysql::Error::MySqlError(MySqlError {
+            state: String::from_str("error").unwrap(),
+            message: format!("invalid sequence: {:?}", err),
+            code: 228,
+        })),
+    }
+}
+
 fn init_database(conn: &mut Conn) -> Result<()> {
     for query in queries::INIT_QUERIES.iter() {
         match conn.query_drop(query) {
@@ -59,27 +108,29 @@ impl Connector {
         query_sequence: String,
         target_sequence_id: String,
         target_sequence: String,
+        seed: u64,
     ) -> Result<String> {
         let hash = job
             .to_hashing_struct(query_sequence.clone(), target_sequence.clone())
             .calculate_hash();
 
-        match self.conn.exec_drop(
-            queries::INSERT_ALIGN_TASK,
-            (
-                &hash,
-                &query_sequence_id,
-                &query_sequence,
-                &target_sequence_id,
-                &target_sequence,
-                job.kd_value,
-                job.r_squared_value,
-                job.del_value,
-                job.dim_value,
-                job.matrices_volume_value,
-                "active",
-            ),
-        ) {
+        let params = Params::Positional(vec![
+            hash.clone().into(),
+            query_sequence_id.into(),
+            query_sequence.into(),
+            target_sequence_id.into(),
+            target_sequence.into(),
+            job.kd_value.into(),
+            job.r_squared_value.into(),
+            job.del_value.into(),
+            job.get_ext_value().into(),
+            job.dim_value.into(),
+            job.matrices_volume_value.into(),
+            "active".into(),
+            seed.into(),
+        ]);
+
+        match self.conn.exec_drop(queries::INSERT_ALIGN_TASK, params) {
             Ok(_) => Ok(hash),
             Err(err) => Err(err),
         }
@@ -98,25 +149,39 @@ impl Connector {
     pub fn insert_align_subtask(
         &mut self,
         hash: String,
-        f_value: f64,
+        alignment: &Alignment<Protein>,
         matrix: &Array2<f64>,
-        sequences: (Vec<Protein>, Vec<Protein>),
+        stats: &AlignmentStats,
     ) -> Result<()> {
         let id = match self.get_align_task_id_by_hash(hash) {
             Ok(id) => id,
             Err(err) => return Err(err),
         };
 
-        match self.conn.exec_drop(
-            queries::INSERT_ALIGN_SUBTASKS,
-            (
-                id,
-                f_value,
-                serde_json::to_string(matrix).unwrap(),
-                Protein::protein_vec_to_string(&sequences.0).unwrap(),
-                Protein::protein_vec_to_string(&sequences.1).unwrap(),
-            ),
-        ) {
+        let result_query_sequence = get_sequence_string(alignment.query())?;
+        let result_target_sequence = get_sequence_string(alignment.target())?;
+
+        // More columns than the tuple conversions of mysql cover
+        let params = Params::Positional(vec![
+            id.into(),
+            alignment.f.into(),
+            serde_json::to_string(matrix).unwrap().into(),
+            result_query_sequence.into(),
+            result_target_sequence.into(),
+            stats.identity.into(),
+            stats.similarity.into(),
+            stats.gaps.into(),
+            stats.gap_opens.into(),
+            stats.aligned_length.into(),
+            stats.query_coverage.into(),
+            stats.target_coverage.into(),
+            alignment.coords.0 .0.into(),
+            alignment.coords.0 .1.into(),
+            alignment.coords.1 .0.into(),
+            alignment.coords.1 .1.into(),
+        ]);
+
+        match self.conn.exec_drop(queries::INSERT_ALIGN_SUBTASKS, params) {
             Ok(_) => Ok(()),
             Err(err) => Err(err),
         }
@@ -136,7 +201,7 @@ impl Connector {
         &mut self,
         hash: String,
     ) -> Result<models::AlignSubtask> {
-        let row: (f64, String, String, String) = match self
+        let row: SubtaskRow = match self
             .conn
             .exec_first(queries::GET_SUBTASK_WITH_MAX_F_VALUE_BY_HASH, (hash,))
         {
@@ -158,6 +223,7 @@ impl Connector {
             matrix_json: row.1,
             result_query_sequence: row.2,
             result_target_sequence: row.3,
+            coords: get_coords((row.4, row.5, row.6, row.7)),
         })
     }
 
@@ -171,6 +237,14 @@ impl Connector {
             Err(err) => return Err(err),
         };
 
+        let ((query_start, query_end), (target_start, target_end)) = match subtask.coords {
+            Some(((query_start, query_end), (target_start, target_end))) => (
+                (Some(query_start), Some(query_end)),
+                (Some(target_start), Some(target_end)),
+            ),
+            None => ((None, None), (None, None)),
+        };
+
         match self.conn.exec_drop(
             queries::INSERT_RESULT_MATRIX,
             (
@@ -179,6 +253,10 @@ impl Connector {
                 subtask.matrix_json,
                 subtask.result_query_sequence,
                 subtask.result_target_sequence,
+                query_start,
+                query_end,
+                target_start,
+                target_end,
             ),
         ) {
             Ok(_) => Ok(()),
@@ -204,7 +282,7 @@ impl Connector {
     }
 
     pub fn get_result_matrix_by_task_id(&mut self, id: i32) -> Result<models::AlignTaskWithMatrix> {
-        let row: (String, String, f64, f64, String) = match self
+        let row: (String, String, f64, f64, f64, String) = match self
             .conn
             .exec_first(queries::GET_RESULT_MATRIX_BY_TASK_ID, (id,))
         {
@@ -226,14 +304,47 @@ impl Connector {
             target_sequence: row.1,
             f_value: row.2,
             del_value: row.3,
-            matrix: serde_json::from_str(&row.4).unwrap(),
+            ext_value: row.4,
+            matrix: serde_json::from_str(&row.5).unwrap(),
+        })
+    }
+
+    pub fn get_result_by_hash(&mut self, hash: String) -> Result<models::AlignResult> {
+        let row: ResultRow = match self.conn.exec_first(queries::GET_RESULT_BY_HASH, (hash,)) {
+            Ok(row) => match row {
+                Some(row_tuple) => row_tuple,
+                None => {
+                    return Err(mysql::Error::MySqlError(MySqlError {
+                        state: String::from_str("error").unwrap(),
+                        message: String::from_str("empty row").unwrap(),
+                        code: 228,
+                    }))
+                }
+            },
+            Err(err) => return Err(err),
+        };
+
+        Ok(models::AlignResult {
+            f_value: row.0,
+            matrix: serde_json::from_str(&row.1).unwrap(),
+            result_query_sequence: row.2.unwrap_or_default(),
+            result_target_sequence: row.3.unwrap_or_default(),
+            p_value: row.4,
+            coords: get_coords((row.5, row.6, row.7, row.8)),
         })
     }
 
-    pub fn add_p_value_by_id(&mut self, p_value: f64, id: i32) -> Result<()> {
+    // The seed is empty for p-values from cached params
+    pub fn add_p_value_by_id(
+        &mut self,
+        p_value: f64,
+        reliable: bool,
+        seed: Option<u64>,
+        id: i32,
+    ) -> Result<()> {
         match self
             .conn
-            .exec_drop(queries::ADD_P_VALUE_BY_ID, (p_value, id))
+            .exec_drop(queries::ADD_P_VALUE_BY_ID, (p_value, reliable, seed, id))
         {
             Ok(_) => Ok(()),
             Err(err) => Err(err),
@@ -264,10 +375,15 @@ impl Connector {
         }
     }
 
-    pub fn add_cmp_p_value_by_id(&mut self, p_value: f64, id: i32) -> Result<()> {
+    pub fn add_cmp_p_value_by_id(
+        &mut self,
+        p_value: f64,
+        seed: Option<u64>,
+        id: i32,
+    ) -> Result<()> {
         match self
             .conn
-            .exec_drop(queries::ADD_CMP_P_VALUE_BY_ID, (p_value, id))
+            .exec_drop(queries::ADD_CMP_P_VALUE_BY_ID, (p_value, seed, id))
         {
             Ok(_) => Ok(()),
             Err(err) => Err(err),
@@ -333,7 +449,27 @@ pub fn get_connection(init: bool) -> Connector {
     Connector { conn }
 }
 
-pub fn get_population(conn: &mut Connector, dim: usize, limit: usize) -> Result<Vec<Array2<f64>>> {
+// Seed for random matrices and shuffles: ALIGNER_SEED if it is set, a random
+// one otherwise. Callers log and store it, so that every run can be repeated
+pub fn get_seed() -> u64 {
+    match env::var("ALIGNER_SEED") {
+        Ok(seed) => match seed.parse() {
+            Ok(seed) => seed,
+            Err(err) => panic!(
+                "Failed with {}. Env value ALIGNER_SEED should be an unsigned integer.",
+                err
+            ),
+        },
+        Err(_) => rand::random(),
+    }
+}
+
+pub fn get_population<R: Rng + ?Sized>(
+    conn: &mut Connector,
+    dim: usize,
+    limit: usize,
+    rng: &mut R,
+) -> Result<Vec<Array2<f64>>> {
     let mut matrices: Vec<Array2<f64>> = match conn.get_base_matrices_with_limit(dim, limit) {
         Ok(matrices) => matrices,
         Err(err) => return Err(err),
@@ -354,8 +490,8 @@ pub fn get_population(conn: &mut Connector, dim: usize, limit: usize) -> Result<
         for _ in matrices.len()..limit {
             let mut check = false;
             while !check {
-                let matrix =
-                    Array2::random((dim, dim), Uniform::new_inclusive(-1, 1)).mapv(|a| a as f64);
+                let matrix = Array2::random_using((dim, dim), Uniform::new_inclusive(-1, 1), rng)
+                    .mapv(|a| a as f64);
 
                 check = true;
                 for item in matrices.iter() {
@@ -1,11 +1,15 @@
 use ndarray::Array2;
 
+// Query and target (start, end) with 1-based starts and exclusive ends
+pub type Coords = ((usize, usize), (usize, usize));
+
 #[derive(Debug, Clone)]
 pub struct AlignSubtask {
     pub f_value: f64,
     pub matrix_json: String,
     pub result_query_sequence: String,
     pub result_target_sequence: String,
+    pub coords: Option<Coords>,
 }
 
 #[derive(Debug, Clone)]
@@ -14,5 +18,17 @@ pub struct AlignTaskWithMatrix {
     pub target_sequence: String,
     pub f_value: f64,
     pub del_value: f64,
+    pub ext_value: f64,
+    pub matrix: Array2<f64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct AlignResult {
+    pub f_value: f64,
     pub matrix: Array2<f64>,
+    pub result_query_sequence: String,
+    pub result_target_sequence: String,
+    pub p_value: Option<f64>,
+    // Empty for results stored before coordinates were recorded
+    pub coords: Option<Coords>,
 }
@@ -14,11 +14,21 @@ pub const INIT_QUERIES: &[&str] = &[
         kd_value double not null,
         r_squared_value double not null,
         del_value double not null,
+        ext_value double,
         dim_value smallint not null,
         matrices_volume_value smallint not null,
         status text not null,
-        p_value double
+        p_value double,
+        p_value_reliable boolean,
+        seed bigint unsigned,
+        p_value_seed bigint unsigned
     )",
+    "alter table align_tasks add column if not exists p_value_reliable boolean",
+    "alter table align_tasks
+        add column if not exists seed bigint unsigned,
+        add column if not exists p_value_seed bigint unsigned",
+    "alter table if exists blast_cmp add column if not exists p_value_seed bigint unsigned",
+    "alter table align_tasks add column if not exists ext_value double",
     "create table if not exists align_subtasks (
         id int primary key auto_increment,
         task_id int not null,
@@ -26,8 +36,32 @@ pub const INIT_QUERIES: &[&str] = &[
         matrix_json json not null,
         result_query_sequence text,
         result_target_sequence text,
+        identity double,
+        similarity double,
+        gaps int,
+        gap_opens int,
+        aligned_length int,
+        query_coverage double,
+        target_coverage double,
+        query_start int,
+        query_end int,
+        target_start int,
+        target_end int,
         foreign key (task_id) references align_tasks (id)
     )",
+    "alter table align_subtasks
+        add column if not exists identity double,
+        add column if not exists similarity double,
+        add column if not exists gaps int,
+        add column if not exists gap_opens int,
+        add column if not exists aligned_length int,
+        add column if not exists query_coverage double,
+        add column if not exists target_coverage double",
+    "alter table align_subtasks
+        add column if not exists query_start int,
+        add column if not exists query_end int,
+        add column if not exists target_start int,
+        add column if not exists target_end int",
     "create table if not exists result_matrices (
         id int primary key auto_increment,
         task_id int not null,
@@ -35,8 +69,17 @@ pub const INIT_QUERIES: &[&str] = &[
         matrix_json json not null,
         result_query_sequence text,
         result_target_sequence text,
+        query_start int,
+        query_end int,
+        target_start int,
+        target_end int,
         foreign key (task_id) references align_tasks (id)
     )",
+    "alter table result_matrices
+        add column if not exists query_start int,
+        add column if not exists query_end int,
+        add column if not exists target_start int,
+        add column if not exists target_end int",
 ];
 
 pub const GET_BASE_MATRICES_WITH_LIMIT: &str = "
@@ -50,8 +93,8 @@ insert into base_matrices (dim, matrix_json) values (?, ?)
 ";
 
 pub const INSERT_ALIGN_TASK: &str = "
-insert into align_tasks (hash, query_sequence_id, query_sequence, target_sequence_id, target_sequence, kd_value, r_squared_value, del_value, dim_value, matrices_volume_value, status)
-values (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+insert into align_tasks (hash, query_sequence_id, query_sequence, target_sequence_id, target_sequence, kd_value, r_squared_value, del_value, ext_value, dim_value, matrices_volume_value, status, seed)
+values (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
 ";
 
 pub const GET_ALIGN_TASK_ID_BY_HASH: &str = "
@@ -60,8 +103,10 @@ where hash = ?
 ";
 
 pub const INSERT_ALIGN_SUBTASKS: &str = "
-insert into align_subtasks (task_id, f_value, matrix_json, result_query_sequence, result_target_sequence)
-values (?, ?, ?, ?, ?)
+insert into align_subtasks (task_id, f_value, matrix_json, result_query_sequence, result_target_sequence,
+    identity, similarity, gaps, gap_opens, aligned_length, query_coverage, target_coverage,
+    query_start, query_end, target_start, target_end)
+values (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
 ";
 
 pub const GET_PERCENTAGE_BY_HASH: &str = "
@@ -72,7 +117,8 @@ having hash = ?
 ";
 
 pub const GET_SUBTASK_WITH_MAX_F_VALUE_BY_HASH: &str = "
-select f_value, matrix_json, result_query_sequence, result_target_sequence from align_subtasks a
+select f_value, matrix_json, result_query_sequence, result_target_sequence,
+    query_start, query_end, target_start, target_end from align_subtasks a
     inner join align_tasks at on a.task_id = at.id
     where hash = ?
 order by f_value
@@ -80,8 +126,9 @@ limit 1
 ";
 
 pub const INSERT_RESULT_MATRIX: &str = "
-insert into result_matrices (task_id, f_value, matrix_json, result_query_sequence, result_target_sequence)
-values (?, ?, ?, ?, ?)
+insert into result_matrices (task_id, f_value, matrix_json, result_query_sequence, result_target_sequence,
+    query_start, query_end, target_start, target_end)
+values (?, ?, ?, ?, ?, ?, ?, ?, ?)
 ";
 
 pub const DELETE_SUBTASKS_BY_HASH: &str = "
@@ -94,14 +141,24 @@ pub const GET_IDS_WITH_NULL_P_VALUE: &str = "
 select id from align_tasks where p_value is null
 ";
 
+// Tasks stored before ext_value were aligned with del_value for both penalties
 pub const GET_RESULT_MATRIX_BY_TASK_ID: &str = "
-select query_sequence, target_sequence, f_value, del_value, matrix_json from result_matrices rm
+select query_sequence, target_sequence, f_value, del_value, coalesce(ext_value, del_value), matrix_json
+    from result_matrices rm
     inner join align_tasks at on rm.task_id = at.id
     where task_id = ?
 ";
 
+pub const GET_RESULT_BY_HASH: &str = "
+select rm.f_value, rm.matrix_json, rm.result_query_sequence, rm.result_target_sequence, at.p_value,
+    rm.query_start, rm.query_end, rm.target_start, rm.target_end from result_matrices rm
+    inner join align_tasks at on rm.task_id = at.id
+    where at.hash = ?
+limit 1
+";
+
 pub const ADD_P_VALUE_BY_ID: &str = "
-update align_tasks set p_value = ? where id = ?
+update align_tasks set p_value = ?, p_value_reliable = ?, p_value_seed = ? where id = ?
 ";
 
 pub const GET_ALL_HASHES: &str = "
@@ -120,5 +177,5 @@ limit 1
 ";
 
 pub const ADD_CMP_P_VALUE_BY_ID: &str = "
-update blast_cmp set p_value = ? where id = ?
+update blast_cmp set p_value = ?, p_value_seed = ? where id = ?
 ";
@@ -24,6 +24,9 @@ pub struct AlignJobRequest {
     pub kd_value: f64,
     pub r_squared_value: f64,
     pub del_value: f64,
+    // Gap extension penalty, the deletion one when it is empty
+    #[serde(default)]
+    pub ext_value: Option<f64>,
     pub dim_value: i32,
     pub matrices_volume_value: i32,
 }
@@ -37,11 +40,16 @@ pub struct AlignJob {
     pub kd_value: f64,
     pub r_squared_value: f64,
     pub del_value: f64,
+    pub ext_value: f64,
     pub matrices_volume_value: i32,
     pub hash: String,
 }
 
 impl AlignJobRequest {
+    pub fn get_ext_value(&self) -> f64 {
+        self.ext_value.unwrap_or(self.del_value)
+    }
+
     pub fn to_hashing_struct(
         &self,
         query_sequence: String,
@@ -53,6 +61,7 @@ impl AlignJobRequest {
             kd_value: format!("{:.5}", self.kd_value),
             r_squared_value: format!("{:.5}", self.r_squared_value),
             del_value: format!("{:.5}", self.del_value),
+            ext_value: format!("{:.5}", self.get_ext_value()),
             dim_value: self.dim_value,
             matrices_volume_value: self.matrices_volume_value,
         }
@@ -105,6 +114,7 @@ pub struct HashingStruct {
     pub kd_value: String,
     pub r_squared_value: String,
     pub del_value: String,
+    pub ext_value: String,
     pub dim_value: i32,
     pub matrices_volume_value: i32,
 }
@@ -125,6 +135,12 @@ pub struct EmptySuccessfulResponseWithHashes {
     pub hashes: Vec<String>,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AlignmentReportResponse {
+    pub hash: String,
+    pub report: String,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct ErroneousResponse {
     pub message: String,